@@ -0,0 +1,94 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+use crate::encryption;
+use crate::export;
+use crate::language::LanguageProfile;
+use crate::puzzle::Puzzle;
+
+/// Answers of the published puzzles, with the dates each appeared on, so
+/// that new grids can stay clear of words solvers saw recently.
+#[derive(Debug, Clone, Default)]
+pub struct Archive {
+    dates: HashMap<String, Vec<String>>,
+    puzzles: usize,
+    /// Puzzle files that could not be read, with the reason.
+    pub skipped: Vec<String>,
+}
+
+impl Archive {
+    /// Reads every `YYYY-MM-DD.txt` puzzle in `dirs` (e.g. `tr` and `en`),
+    /// decrypting encrypted ones, and normalizes their answers the way
+    /// `language` normalizes dictionary words. Puzzles that do not parse
+    /// are listed in `skipped` instead of failing the scan.
+    pub fn scan(dirs: &[String], language: &LanguageProfile) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut archive = Self::default();
+        for dir in dirs {
+            let mut paths: Vec<String> = fs::read_dir(dir)?
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| entry.path().to_str().map(str::to_string))
+                .filter(|path| path.ends_with(".txt"))
+                .collect();
+            paths.sort();
+
+            for path in paths {
+                let Some(date) = encryption::date_from_path(&path) else {
+                    continue;
+                };
+                match Puzzle::load(&path) {
+                    Ok(puzzle) => {
+                        archive.puzzles += 1;
+                        for entry in puzzle.entries() {
+                            if entry.answer.chars().count() >= 2 {
+                                archive.add(&language.normalize_word(&entry.answer), &date);
+                            }
+                        }
+                    }
+                    Err(e) => archive.skipped.push(e.to_string()),
+                }
+            }
+        }
+        Ok(archive)
+    }
+
+    /// Records that `word` was an answer on `date`.
+    pub fn add(&mut self, word: &str, date: &str) {
+        let dates = self.dates.entry(word.to_string()).or_default();
+        if !dates.iter().any(|known| known == date) {
+            dates.push(date.to_string());
+        }
+    }
+
+    /// Latest date `word` was an answer on.
+    pub fn last_used(&self, word: &str) -> Option<&str> {
+        self.dates.get(word)?.iter().max().map(String::as_str)
+    }
+
+    /// Number of puzzles read.
+    pub fn puzzles(&self) -> usize {
+        self.puzzles
+    }
+
+    /// Number of distinct answers.
+    pub fn len(&self) -> usize {
+        self.dates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dates.is_empty()
+    }
+
+    /// Answers that appeared less than `cooldown` days before or after
+    /// `date`; later puzzles count too, as they may already be scheduled.
+    pub fn recent_words(&self, date: &str, cooldown: usize) -> Result<HashSet<String>, String> {
+        let mut recent = HashSet::new();
+        for (word, dates) in &self.dates {
+            for used in dates {
+                if export::days_between(used, date)?.unsigned_abs() < cooldown as u64 {
+                    recent.insert(word.clone());
+                }
+            }
+        }
+        Ok(recent)
+    }
+}
@@ -0,0 +1,379 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+use crate::language::LanguageProfile;
+use crate::{CrosswordGraph, GraphConfig, WordLayer};
+
+// Binary cache layout (all integers are LEB128 varints unless noted):
+//
+//   magic "XWGRAPH\0" | version u32 LE | source hash u64 LE | config
+//   words | checked words | intermediaries (string lists)
+//   scores: count, then (word index, score) pairs
+//   liners, each followed by its intermediary index list
+//
+// The pattern index is rebuilt from the words on load.
+//
+// Bump CACHE_VERSION whenever the layout or the graph building rules change.
+const CACHE_MAGIC: &[u8; 8] = b"XWGRAPH\0";
+pub const CACHE_VERSION: u32 = 3;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// FNV-1a, chosen because its output is stable across Rust releases and
+/// platforms, unlike `DefaultHasher`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Fnv64(u64);
+
+impl Fnv64 {
+    pub(crate) fn new() -> Self {
+        Self(FNV_OFFSET_BASIS)
+    }
+
+    pub(crate) fn from_state(state: u64) -> Self {
+        Self(state)
+    }
+
+    pub(crate) fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    pub(crate) fn write_str(&mut self, text: &str) {
+        self.write(&(text.len() as u64).to_le_bytes());
+        self.write(text.as_bytes());
+    }
+
+    pub(crate) fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+fn hash_config(hasher: &mut Fnv64, config: &GraphConfig) {
+    hasher.write(&CACHE_VERSION.to_le_bytes());
+    for value in [config.width, config.height, config.min_word_length] {
+        hasher.write(&(value as u64).to_le_bytes());
+    }
+    hasher.write(&[config.intermediaries as u8]);
+    let language = &config.language;
+    hasher.write_str(&language.code);
+    hasher.write_str(&language.alphabet);
+    hasher.write_str(language.locale.code());
+    for (letters, cell) in &language.digraphs {
+        hasher.write_str(letters);
+        hasher.write(&(*cell as u32).to_le_bytes());
+    }
+}
+
+impl CrosswordGraph {
+    /// Key identifying the dictionaries and configuration this graph was
+    /// built from. A cache file is only reused when its key matches.
+    pub fn cache_key(&self) -> u64 {
+        let mut hasher = Fnv64::from_state(self.source_hash);
+        hash_config(&mut hasher, &self.config);
+        hasher.finish()
+    }
+
+    /// Cache key a graph built from `layers` with `config` would have,
+    /// computed without building it.
+    pub fn cache_key_for(layers: &[WordLayer], config: &GraphConfig) -> Result<u64, Box<dyn std::error::Error>> {
+        let mut graph = CrosswordGraph::new(config.clone());
+        for layer in layers {
+            graph.hash_source(layer.kind.name().as_bytes());
+            graph.hash_source(&fs::read(&layer.path)?);
+        }
+        Ok(graph.cache_key())
+    }
+
+    /// Reuses the graph cached at `cache_path` if it was built from the same
+    /// word lists and config. Otherwise builds it and rewrites the cache.
+    pub fn load_or_build(
+        cache_path: &str,
+        layers: &[WordLayer],
+        config: GraphConfig,
+        verbose: bool,
+        parallel: bool,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let expected_key = Self::cache_key_for(layers, &config)?;
+        match Self::load(cache_path) {
+            Ok(graph) if graph.cache_key() == expected_key => {
+                if verbose {
+                    println!("Loaded graph from cache {}", cache_path);
+                }
+                return Ok(graph);
+            }
+            Ok(_) => {
+                if verbose {
+                    println!("Cache {} is stale, rebuilding...", cache_path);
+                }
+            }
+            Err(e) => {
+                if verbose {
+                    println!("Cache {} unavailable ({}), building...", cache_path, e);
+                }
+            }
+        }
+
+        let graph = Self::build_layers(config, layers, verbose, parallel)?;
+        graph.save(cache_path)?;
+        if verbose {
+            println!("Saved graph cache to {}", cache_path);
+        }
+        Ok(graph)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let mut writer = Writer::default();
+        writer.bytes(CACHE_MAGIC);
+        writer.bytes(&CACHE_VERSION.to_le_bytes());
+        writer.bytes(&self.source_hash.to_le_bytes());
+        writer.config(&self.config);
+
+        let words = sorted(&self.words);
+        writer.strings(&words);
+        writer.strings(&sorted(&self.words_that_can_be_checked_against));
+
+        let intermediaries = sorted(&self.real_intermediaries);
+        let intermediary_index = index_of(&intermediaries);
+        writer.strings(&intermediaries);
+
+        let scores: Vec<(usize, u32)> = words
+            .iter()
+            .enumerate()
+            .filter_map(|(id, word)| Some((id, *self.scores.get(word.as_str())?)))
+            .collect();
+        writer.varint(scores.len() as u64);
+        for (id, score) in scores {
+            writer.varint(id as u64);
+            writer.varint(score as u64);
+        }
+
+        let liners = sorted(&self.liners);
+        writer.varint(liners.len() as u64);
+        let empty = HashSet::new();
+        for liner in liners {
+            writer.string(liner);
+            let children = self.liner_to_intermediaries.get(liner).unwrap_or(&empty);
+            writer.indices(children, &intermediary_index);
+        }
+
+        fs::write(path, writer.buf)?;
+        Ok(())
+    }
+
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let data = fs::read(path)?;
+        let mut reader = Reader { buf: &data, pos: 0 };
+
+        if reader.take(CACHE_MAGIC.len())? != CACHE_MAGIC {
+            return Err(format!("{} is not a crossword graph cache", path).into());
+        }
+        let version = u32::from_le_bytes(reader.take(4)?.try_into()?);
+        if version != CACHE_VERSION {
+            return Err(format!(
+                "{} has cache version {}, expected {}",
+                path, version, CACHE_VERSION
+            )
+            .into());
+        }
+        let source_hash = u64::from_le_bytes(reader.take(8)?.try_into()?);
+        let config = reader.config()?;
+
+        let mut graph = CrosswordGraph::new(config);
+        graph.source_hash = source_hash;
+
+        let words = reader.strings()?;
+        graph.words_that_can_be_checked_against = reader.strings()?.into_iter().collect();
+
+        let intermediaries = reader.strings()?;
+        graph.real_intermediaries = intermediaries.iter().cloned().collect();
+
+        for _ in 0..reader.varint()? {
+            let id = reader.usize()?;
+            let word = words.get(id).ok_or_else(|| format!("index {} out of range in cache file", id))?;
+            let score = u32::try_from(reader.varint()?)?;
+            graph.scores.insert(word.clone(), score);
+        }
+        graph.words = words.into_iter().collect();
+
+        for _ in 0..reader.varint()? {
+            let liner = reader.string()?;
+            let children = reader.lookup(&intermediaries)?;
+            if !children.is_empty() {
+                graph.liner_to_intermediaries.insert(liner.clone(), children);
+            }
+            graph.liners.insert(liner);
+        }
+
+        if reader.pos != data.len() {
+            return Err(format!("{} has trailing data", path).into());
+        }
+        graph.build_graph(false);
+
+        Ok(graph)
+    }
+
+    pub(crate) fn hash_source(&mut self, bytes: &[u8]) {
+        let mut hasher = Fnv64::from_state(self.source_hash);
+        hasher.write(&(bytes.len() as u64).to_le_bytes());
+        hasher.write(bytes);
+        self.source_hash = hasher.finish();
+    }
+}
+
+fn sorted(set: &HashSet<String>) -> Vec<&String> {
+    let mut items: Vec<&String> = set.iter().collect();
+    items.sort();
+    items
+}
+
+fn index_of<'a>(items: &[&'a String]) -> HashMap<&'a str, usize> {
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| (item.as_str(), i))
+        .collect()
+}
+
+#[derive(Default)]
+struct Writer {
+    buf: Vec<u8>,
+}
+
+impl Writer {
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.buf.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        self.buf.push(value as u8);
+    }
+
+    fn string(&mut self, text: &str) {
+        self.varint(text.len() as u64);
+        self.bytes(text.as_bytes());
+    }
+
+    fn strings(&mut self, items: &[&String]) {
+        self.varint(items.len() as u64);
+        for item in items {
+            self.string(item);
+        }
+    }
+
+    fn indices(&mut self, items: &HashSet<String>, index: &HashMap<&str, usize>) {
+        let mut ids: Vec<usize> = items.iter().filter_map(|item| index.get(item.as_str()).copied()).collect();
+        ids.sort_unstable();
+        self.varint(ids.len() as u64);
+        for id in ids {
+            self.varint(id as u64);
+        }
+    }
+
+    fn config(&mut self, config: &GraphConfig) {
+        self.varint(config.width as u64);
+        self.varint(config.height as u64);
+        self.varint(config.min_word_length as u64);
+        self.varint(config.intermediaries as u64);
+        let language = &config.language;
+        self.string(&language.code);
+        self.string(&language.alphabet);
+        self.string(language.locale.code());
+        self.varint(language.digraphs.len() as u64);
+        for (letters, cell) in &language.digraphs {
+            self.string(letters);
+            self.varint(*cell as u64);
+        }
+        self.string(&language.dictionary);
+    }
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(len).filter(|&end| end <= self.buf.len());
+        let end = end.ok_or("truncated cache file")?;
+        let bytes = &self.buf[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("malformed varint in cache file".to_string())
+    }
+
+    fn usize(&mut self) -> Result<usize, String> {
+        usize::try_from(self.varint()?).map_err(|e| e.to_string())
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let len = self.usize()?;
+        String::from_utf8(self.take(len)?.to_vec()).map_err(|e| e.to_string())
+    }
+
+    fn strings(&mut self) -> Result<Vec<String>, String> {
+        (0..self.usize()?).map(|_| self.string()).collect()
+    }
+
+    fn lookup(&mut self, table: &[String]) -> Result<HashSet<String>, String> {
+        (0..self.usize()?)
+            .map(|_| {
+                let id = self.usize()?;
+                table.get(id).cloned().ok_or_else(|| format!("index {} out of range in cache file", id))
+            })
+            .collect()
+    }
+
+    fn config(&mut self) -> Result<GraphConfig, String> {
+        let width = self.usize()?;
+        let height = self.usize()?;
+        let min_word_length = self.usize()?;
+        let intermediaries = self.varint()? != 0;
+        let code = self.string()?;
+        let alphabet = self.string()?;
+        let locale = self.string()?.parse()?;
+        let digraphs = (0..self.usize()?)
+            .map(|_| {
+                let letters = self.string()?;
+                let cell = u32::try_from(self.varint()?)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or("invalid digraph cell in cache file")?;
+                Ok((letters, cell))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        let dictionary = self.string()?;
+
+        Ok(GraphConfig {
+            width,
+            height,
+            min_word_length,
+            intermediaries,
+            language: LanguageProfile {
+                code,
+                alphabet,
+                locale,
+                digraphs,
+                dictionary,
+            },
+        })
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::grid::Direction;
+use crate::puzzle::{Entry, Puzzle};
+use crate::{CrosswordGraph, Word};
+
+/// Which entry lengths a puzzle may use.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LengthPolicy {
+    /// Entries shorter than this are illegal, single letters aside.
+    pub min_length: usize,
+    /// Entries longer than this are illegal.
+    pub max_length: Option<usize>,
+    /// Lets single-letter entries through unchecked, as the archive uses
+    /// them for letters like the "K" of potassium.
+    pub allow_single_letters: bool,
+}
+
+impl Default for LengthPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 2,
+            max_length: None,
+            allow_single_letters: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// In the dictionary.
+    Word,
+    /// A single letter the policy lets through.
+    Unchecked,
+    TooShort,
+    TooLong,
+    /// Not in the dictionary.
+    Unknown,
+}
+
+impl Verdict {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, Verdict::Word | Verdict::Unchecked)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryReport {
+    pub entry: Entry,
+    /// The answer normalized the way the dictionary is.
+    pub word: String,
+    pub verdict: Verdict,
+    /// Dictionary words that could replace an unknown answer. They keep
+    /// every letter shared with a valid crossing entry when any such word
+    /// exists, otherwise they are the closest words of the same length.
+    pub suggestions: Vec<String>,
+}
+
+/// Checks every entry of `puzzle` against the graph's dictionary, including
+/// words of any length (`words_that_can_be_checked_against`), and suggests
+/// up to `max_suggestions` replacements for unknown ones.
+pub fn check_puzzle(puzzle: &Puzzle, graph: &CrosswordGraph, policy: &LengthPolicy, max_suggestions: usize) -> Vec<EntryReport> {
+    let language = &graph.config().language;
+    let dictionary = graph.words_that_can_be_checked_against();
+
+    let mut reports: Vec<EntryReport> = puzzle
+        .entries()
+        .into_iter()
+        .map(|entry| {
+            let word = language.normalize_word(&entry.answer);
+            let length = word.chars().count();
+            let verdict = if length == 1 && policy.allow_single_letters {
+                Verdict::Unchecked
+            } else if length < policy.min_length {
+                Verdict::TooShort
+            } else if policy.max_length.is_some_and(|max| length > max) {
+                Verdict::TooLong
+            } else if dictionary.contains(&word) {
+                Verdict::Word
+            } else {
+                Verdict::Unknown
+            };
+            EntryReport {
+                entry,
+                word,
+                verdict,
+                suggestions: Vec::new(),
+            }
+        })
+        .collect();
+
+    // Which across and down entry covers each cell
+    let mut covering: HashMap<(usize, usize, Direction), usize> = HashMap::new();
+    for (index, report) in reports.iter().enumerate() {
+        for cell in cells(&report.entry) {
+            covering.insert((cell.0, cell.1, report.entry.id.direction), index);
+        }
+    }
+
+    for index in 0..reports.len() {
+        if reports[index].verdict != Verdict::Unknown {
+            continue;
+        }
+        let report = &reports[index];
+        let crossing = match report.entry.id.direction {
+            Direction::Horizontal => Direction::Vertical,
+            Direction::Vertical => Direction::Horizontal,
+        };
+
+        // Keep letters that a valid crossing word depends on
+        if report.word.chars().count() != report.entry.answer.chars().count() {
+            continue;
+        }
+        let pattern: Word = cells(&report.entry)
+            .zip(report.word.chars())
+            .map(|((row, col), letter)| match covering.get(&(row, col, crossing)) {
+                Some(&other) if reports[other].verdict == Verdict::Word => letter,
+                _ => '_',
+            })
+            .collect();
+
+        let mut suggestions = closest_words(dictionary, &report.word, &pattern, max_suggestions);
+        if suggestions.is_empty() {
+            let anything: Word = pattern.chars().iter().map(|_| '_').collect();
+            suggestions = closest_words(dictionary, &report.word, &anything, max_suggestions);
+        }
+        reports[index].suggestions = suggestions;
+    }
+
+    reports
+}
+
+fn cells(entry: &Entry) -> impl Iterator<Item = (usize, usize)> + '_ {
+    (0..entry.answer.chars().count()).map(move |i| match entry.id.direction {
+        Direction::Horizontal => (entry.row, entry.col + i),
+        Direction::Vertical => (entry.row + i, entry.col),
+    })
+}
+
+/// Words other than `current` that fit `pattern`, most letters in common
+/// with `current` first.
+fn closest_words(dictionary: &HashSet<String>, current: &str, pattern: &Word, limit: usize) -> Vec<String> {
+    let current_word = Word::new(current);
+    let mut candidates: Vec<(usize, &String)> = dictionary
+        .iter()
+        .filter(|word| *word != current)
+        .map(|word| (Word::new(word), word))
+        .filter(|(word, _)| word.matches(pattern))
+        .map(|(word, text)| {
+            let shared = word.chars().iter().zip(current_word.chars()).filter(|(a, b)| a == b).count();
+            (shared, text)
+        })
+        .collect();
+    candidates.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+    candidates.into_iter().take(limit).map(|(_, word)| word.clone()).collect()
+}
@@ -0,0 +1,339 @@
+use clap::{Parser, Subcommand};
+use crossword_grapher::check::{self, LengthPolicy, Verdict};
+use crossword_grapher::{
+    encryption, CrosswordGraph, CrosswordGrid, CrosswordSolver, GraphConfig, LanguageProfile, Puzzle, Template,
+    Uniqueness, WordLayer,
+};
+use std::fs;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[derive(Parser)]
+#[command(author, version, about = "Tools for the published puzzle files", long_about = None)]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Check puzzle files (e.g. tr/*.txt) for format errors and missing or
+    /// extra clues, decrypting encrypted ones
+    Lint {
+        /// Puzzle files to check
+        #[arg(required = true)]
+        files: Vec<String>,
+    },
+    /// Check that every entry of the puzzles is a dictionary word of a legal
+    /// length, suggesting replacements for unknown ones
+    Check {
+        /// Puzzle files to check
+        #[arg(required = true)]
+        files: Vec<String>,
+
+        /// Language code (tr, en) or path to a language profile file
+        #[arg(long, default_value = "tr")]
+        lang: String,
+
+        /// Dictionary file (default: the language's dictionary)
+        #[arg(short, long)]
+        input: Option<String>,
+
+        /// Shortest legal entry; single letters are allowed unless
+        /// --check-single-letters is given
+        #[arg(long, default_value_t = LengthPolicy::default().min_length)]
+        min_len: usize,
+
+        /// Longest legal entry
+        #[arg(long)]
+        max_len: Option<usize>,
+
+        /// Require single-letter entries to be dictionary words too
+        #[arg(long)]
+        check_single_letters: bool,
+
+        /// Number of replacements to suggest for an unknown word
+        #[arg(long, default_value = "5")]
+        suggestions: usize,
+    },
+    /// Search for other fills of a puzzle's block layout to check that its
+    /// answer is unique
+    Unique(UniqueArgs),
+    /// Encrypt a puzzle file, by default the way encrypt.html does
+    Encrypt {
+        /// Plain puzzle file
+        input: String,
+
+        /// Output file (default: stdout); may be the input file
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// v1 ([ENCRYPTED], XOR) or v2 ([ENCRYPTED:v2], keyed with the date)
+        #[arg(long, default_value = "v1")]
+        format: encryption::Format,
+
+        /// Publish date for v2 (default: from the output or input file name)
+        #[arg(long)]
+        date: Option<String>,
+    },
+    /// Decrypt an [ENCRYPTED] or [ENCRYPTED:v2] puzzle file
+    Decrypt {
+        /// Encrypted puzzle file
+        input: String,
+
+        /// Output file (default: stdout); may be the input file
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Publish date for v2 (default: from the input file name)
+        #[arg(long)]
+        date: Option<String>,
+    },
+}
+
+#[derive(clap::Args)]
+struct UniqueArgs {
+    /// Puzzle file
+    file: String,
+
+    /// Language code (tr, en) or path to a language profile file
+    #[arg(long, default_value = "tr")]
+    lang: String,
+
+    /// Dictionary file (default: the language's dictionary)
+    #[arg(short, long)]
+    input: Option<String>,
+
+    /// Minimum word length (default: the puzzle's shortest entry of two
+    /// or more letters)
+    #[arg(long)]
+    min_len: Option<usize>,
+
+    /// Graph cache file, reused when it matches the dictionary and config
+    #[arg(long)]
+    cache: Option<String>,
+
+    /// Stop after finding this many other fills
+    #[arg(long, default_value = "3")]
+    alternatives: usize,
+
+    /// Maximum number of search attempts
+    #[arg(short, long, default_value = "100000")]
+    max_attempts: usize,
+
+    /// Stop the search after this many seconds
+    #[arg(short, long)]
+    timeout: Option<f64>,
+
+    /// Random seed for reproducible results
+    #[arg(short, long)]
+    seed: Option<u64>,
+}
+
+fn write_output(output: Option<&str>, content: &str) -> Result<(), Box<dyn std::error::Error>> {
+    match output {
+        Some(path) => fs::write(path, content)?,
+        None => println!("{}", content),
+    }
+    Ok(())
+}
+
+/// Lints every file, printing its problems. Returns whether all passed.
+fn lint(files: &[String]) -> bool {
+    let mut failed = 0;
+
+    for file in files {
+        let content = match fs::read_to_string(file) {
+            Ok(content) => content,
+            Err(e) => {
+                println!("{}: {}", file, e);
+                failed += 1;
+                continue;
+            }
+        };
+        let date = encryption::date_from_path(file);
+        let problems = match Puzzle::parse_dated(&content, date.as_deref()) {
+            Ok(puzzle) => puzzle.problems(),
+            Err(e) => vec![e],
+        };
+        if problems.is_empty() {
+            println!("{}: ok", file);
+        } else {
+            failed += 1;
+            for problem in problems {
+                println!("{}: {}", file, problem);
+            }
+        }
+    }
+
+    println!(
+        "\n{} checked: {} ok, {} with problems",
+        files.len(),
+        files.len() - failed,
+        failed
+    );
+    failed == 0
+}
+
+/// Prints a report of every entry. Returns whether all entries passed.
+fn check_entries(files: &[String], graph: &CrosswordGraph, policy: &LengthPolicy, suggestions: usize) -> bool {
+    let language = &graph.config().language;
+    let display = |word: &str| language.locale.to_uppercase(&language.expand_digraphs(word));
+    let mut problems = 0;
+
+    for file in files {
+        println!("{}", file);
+        let puzzle = match Puzzle::load(file) {
+            Ok(puzzle) => puzzle,
+            Err(e) => {
+                println!("  {}", e);
+                problems += 1;
+                continue;
+            }
+        };
+
+        for report in check::check_puzzle(&puzzle, graph, policy, suggestions) {
+            let status = match report.verdict {
+                Verdict::Word => "ok".to_string(),
+                Verdict::Unchecked => "single letter, not checked".to_string(),
+                Verdict::TooShort => format!("too short, entries need {} letters", policy.min_length),
+                Verdict::TooLong => format!("too long, entries may have {} letters", policy.max_length.unwrap_or(0)),
+                Verdict::Unknown if report.suggestions.is_empty() => "not in the dictionary".to_string(),
+                Verdict::Unknown => {
+                    let suggestions: Vec<String> = report.suggestions.iter().map(|word| display(word)).collect();
+                    format!("not in the dictionary, try {}", suggestions.join(", "))
+                }
+            };
+            if !report.verdict.is_ok() {
+                problems += 1;
+            }
+            println!("  {:<6} {:<10} {}", report.entry.id.to_string(), report.entry.answer, status);
+        }
+    }
+
+    println!("\n{} problem(s) in {} file(s)", problems, files.len());
+    problems == 0
+}
+
+/// Looks for other fills of the puzzle's block layout. Returns whether its
+/// fill was proven unique.
+fn check_unique(args: &UniqueArgs) -> Result<bool, Box<dyn std::error::Error>> {
+    let UniqueArgs {
+        file,
+        lang,
+        input,
+        min_len,
+        cache,
+        alternatives,
+        max_attempts,
+        timeout,
+        seed,
+    } = args;
+
+    let puzzle = Puzzle::load(file)?;
+    let language = LanguageProfile::resolve(lang)?;
+    let input = language.dictionary_or(input.as_deref())?;
+    let rows: Vec<String> = puzzle.rows.iter().map(|row| row.iter().collect()).collect();
+    let template = Template::parse(&rows.join("\n"), &language).map_err(|e| format!("{}: {}", file, e))?;
+    let min_len = min_len.unwrap_or_else(|| {
+        let lengths = puzzle.entries().into_iter().map(|entry| entry.answer.chars().count());
+        lengths.filter(|&length| length >= 2).min().unwrap_or(2)
+    });
+
+    // The layout is fixed, so every run between blocks takes one word
+    let config = GraphConfig {
+        language,
+        intermediaries: false,
+        ..GraphConfig::square(puzzle.size(), min_len)
+    };
+    let graph = Arc::new(match cache {
+        Some(cache) => CrosswordGraph::load_or_build(cache, &[WordLayer::base(&input)], config, false, true)?,
+        None => CrosswordGraph::build(config, &input, false, true)?,
+    });
+    let solution = CrosswordGrid::from_template(graph.clone(), &template)?;
+
+    let mut solver = CrosswordSolver::new(graph, *seed, *max_attempts);
+    if let Some(timeout) = timeout {
+        solver = solver.with_deadline(Instant::now() + Duration::from_secs_f64(*timeout));
+    }
+    let uniqueness = solver.find_alternatives(&solution, *alternatives, false);
+
+    match uniqueness {
+        Uniqueness::Unique => {
+            println!("{}: unique, no other fill exists ({} attempts)", file, solver.attempts());
+            Ok(true)
+        }
+        Uniqueness::Alternatives(grids) => {
+            println!("{}: not unique, {} other fill(s) found:", file, grids.len());
+            for grid in grids {
+                grid.print_grid();
+            }
+            Ok(false)
+        }
+        Uniqueness::Undecided => {
+            println!(
+                "{}: undecided, no other fill found within {} attempts",
+                file,
+                solver.attempts()
+            );
+            Ok(false)
+        }
+    }
+}
+
+fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let args = Args::parse();
+
+    let ok = match &args.command {
+        Command::Lint { files } => lint(files),
+        Command::Check {
+            files,
+            lang,
+            input,
+            min_len,
+            max_len,
+            check_single_letters,
+            suggestions,
+        } => {
+            let language = LanguageProfile::resolve(lang)?;
+            let input = language.dictionary_or(input.as_deref())?;
+            // Only the word lists are needed, not the liners
+            let mut graph = CrosswordGraph::new(GraphConfig {
+                language,
+                ..GraphConfig::default()
+            });
+            graph.load_words(&input, false)?;
+
+            let policy = LengthPolicy {
+                min_length: *min_len,
+                max_length: *max_len,
+                allow_single_letters: !check_single_letters,
+            };
+            check_entries(files, &graph, &policy, *suggestions)
+        }
+        Command::Unique(args) => check_unique(args)?,
+        Command::Encrypt { input, output, format, date } => {
+            let content = fs::read_to_string(input)?;
+            if encryption::is_encrypted(&content) {
+                return Err(format!("{} is already encrypted", input).into());
+            }
+            let date = date
+                .clone()
+                .or_else(|| output.as_deref().and_then(encryption::date_from_path))
+                .or_else(|| encryption::date_from_path(input));
+            let encrypted = encryption::encrypt_as(&content, *format, date.as_deref())?;
+            write_output(output.as_deref(), &encrypted)?;
+            true
+        }
+        Command::Decrypt { input, output, date } => {
+            let content = fs::read_to_string(input)?;
+            let date = date.clone().or_else(|| encryption::date_from_path(input));
+            let plain = encryption::decrypt(&content, date.as_deref()).map_err(|e| format!("{}: {}", input, e))?;
+            write_output(output.as_deref(), &plain)?;
+            true
+        }
+    };
+
+    Ok(if ok { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
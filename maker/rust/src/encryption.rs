@@ -0,0 +1,174 @@
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+use crate::export;
+
+/// Marker the web player looks for before decrypting a puzzle file.
+pub const ENCRYPTED_MARKER: &str = "[ENCRYPTED]";
+
+/// Marker of the keyed format, see [`Format::V2`].
+pub const ENCRYPTED_MARKER_V2: &str = "[ENCRYPTED:v2]";
+
+// Key of `simpleEncrypt` in encrypt.html and `simpleDecrypt` in puzzle-loader.js
+const XOR_KEY: u16 = 42;
+
+// HKDF parameters of the v2 format. Changing them breaks every v2 file.
+const V2_SALT: &[u8] = b"crossword-puzzle";
+const V2_INFO: &[u8] = b"[ENCRYPTED:v2] aes-256-gcm";
+const V2_NONCE_LEN: usize = 12;
+
+/// How a puzzle file is encrypted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// `[ENCRYPTED]`: every UTF-16 code unit XORed with 42, as encrypt.html
+    /// writes it.
+    #[default]
+    V1,
+    /// `[ENCRYPTED:v2]`: AES-256-GCM with a random 96-bit nonce, keyed with
+    /// HKDF-SHA256 of the publish date. The body is base64 of the nonce
+    /// followed by the ciphertext and tag, which WebCrypto can decrypt with
+    /// `HKDF` and `AES-GCM`.
+    V2,
+}
+
+impl Format {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::V1 => "v1",
+            Format::V2 => "v2",
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "v1" => Ok(Format::V1),
+            "v2" => Ok(Format::V2),
+            _ => Err(format!("unknown encryption format '{}': expected v1 or v2", name)),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// XORs every UTF-16 code unit with 42, exactly like `simpleEncrypt`.
+/// Surrogates stay surrogates under the key, so the result is always valid
+/// and applying it twice gives the text back.
+pub fn xor(text: &str) -> String {
+    let units: Vec<u16> = text.encode_utf16().map(|unit| unit ^ XOR_KEY).collect();
+    String::from_utf16(&units).expect("XOR with 42 keeps surrogate pairs intact")
+}
+
+/// The format `text` is encrypted in, if any.
+pub fn format_of(text: &str) -> Option<Format> {
+    let text = text.trim();
+    if text.starts_with(ENCRYPTED_MARKER) {
+        Some(Format::V1)
+    } else if text.starts_with(ENCRYPTED_MARKER_V2) {
+        Some(Format::V2)
+    } else {
+        None
+    }
+}
+
+pub fn is_encrypted(text: &str) -> bool {
+    format_of(text).is_some()
+}
+
+/// Encrypts a puzzle the way encrypt.html does: the trimmed text is XORed
+/// and put after an `[ENCRYPTED]` line.
+pub fn encrypt(text: &str) -> String {
+    format!("{}\n{}", ENCRYPTED_MARKER, xor(text.trim()))
+}
+
+/// Encrypts the trimmed text in the v2 format for the puzzle published on
+/// `date` (`YYYY-MM-DD`).
+pub fn encrypt_v2(text: &str, date: &str) -> Result<String, String> {
+    let cipher = v2_cipher(date)?;
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, text.trim().as_bytes())
+        .map_err(|_| "encryption failed".to_string())?;
+
+    let mut body = nonce.to_vec();
+    body.extend_from_slice(&ciphertext);
+    Ok(format!("{}\n{}", ENCRYPTED_MARKER_V2, BASE64.encode(body)))
+}
+
+/// Encrypts `text` in `format`; v2 needs the publish date.
+pub fn encrypt_as(text: &str, format: Format, date: Option<&str>) -> Result<String, String> {
+    match (format, date) {
+        (Format::V1, _) => Ok(encrypt(text)),
+        (Format::V2, Some(date)) => encrypt_v2(text, date),
+        (Format::V2, None) => Err("v2 encryption needs the puzzle's publish date".to_string()),
+    }
+}
+
+/// Decrypts a file in either format. v2 files need their publish date,
+/// which is normally the file name (see [`date_from_path`]).
+pub fn decrypt(text: &str, date: Option<&str>) -> Result<String, String> {
+    let text = text.trim();
+    if let Some(body) = text.strip_prefix(ENCRYPTED_MARKER) {
+        // The player skips one code unit after the marker, normally the newline
+        let mut chars = body.chars();
+        chars.next();
+        return Ok(xor(chars.as_str()));
+    }
+
+    let body = text
+        .strip_prefix(ENCRYPTED_MARKER_V2)
+        .ok_or_else(|| format!("text does not start with {} or {}", ENCRYPTED_MARKER, ENCRYPTED_MARKER_V2))?;
+    let date = date.ok_or("v2 puzzles need their publish date to decrypt")?;
+    let body = BASE64
+        .decode(body.trim())
+        .map_err(|e| format!("invalid v2 puzzle body: {}", e))?;
+    if body.len() < V2_NONCE_LEN {
+        return Err("v2 puzzle body is too short".to_string());
+    }
+    let (nonce, ciphertext) = body.split_at(V2_NONCE_LEN);
+    let plain = v2_cipher(date)?
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| format!("cannot decrypt v2 puzzle with date {}, or it was modified", date))?;
+    String::from_utf8(plain).map_err(|e| e.to_string())
+}
+
+/// The plain text of a puzzle file, decrypting it if needed, as the web
+/// player's `processPuzzleText` does.
+pub fn plain_text(text: &str, date: Option<&str>) -> Result<String, String> {
+    if is_encrypted(text) {
+        decrypt(text, date)
+    } else {
+        Ok(text.trim().to_string())
+    }
+}
+
+/// Publish date of a puzzle file named `YYYY-MM-DD.txt`.
+pub fn date_from_path(path: &str) -> Option<String> {
+    let stem = Path::new(path).file_stem()?.to_str()?;
+    export::check_date(stem).ok()?;
+    Some(stem.to_string())
+}
+
+fn v2_cipher(date: &str) -> Result<Aes256Gcm, String> {
+    export::check_date(date)?;
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(V2_SALT), date.as_bytes())
+        .expand(V2_INFO, &mut key)
+        .map_err(|e| e.to_string())?;
+    Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)))
+}
@@ -0,0 +1,172 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::encryption;
+use crate::grid::CrosswordGrid;
+use crate::puzzle::{Clue, Puzzle, BLOCK};
+
+/// Renders a solved grid in the format the web player loads from
+/// `tr/YYYY-MM-DD.txt` and `en/YYYY-MM-DD.txt`:
+///
+/// ```text
+/// AUTHOR: Cemre
+/// GRID: 5
+/// -ASUS
+/// ORAYA
+/// ...
+///
+/// H1.1: TODO (ASUS)
+/// ...
+/// V1.1: TODO (OH)
+/// V1.2: TODO (K)
+/// ```
+///
+/// Letters are uppercased with the language's locale and blocks become `-`.
+/// Every [`Entry`](crate::puzzle::Entry), single letters included, gets a
+/// clue stub.
+pub fn puzzle_text(grid: &CrosswordGrid, author: &str) -> Result<String, String> {
+    if !grid.is_complete() {
+        return Err(format!(
+            "grid is not complete: {}/{} cells filled",
+            grid.filled_cells(),
+            grid.open_cells()
+        ));
+    }
+    if grid.width() != grid.height() {
+        return Err(format!(
+            "the site's puzzle format needs a square grid, not {}x{}",
+            grid.width(),
+            grid.height()
+        ));
+    }
+
+    let locale = grid.language().locale;
+    let rows = grid
+        .cells()
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|&cell| match cell {
+                    '@' => BLOCK,
+                    letter => {
+                        let upper = locale.to_uppercase(&letter.to_string());
+                        let mut chars = upper.chars();
+                        match (chars.next(), chars.next()) {
+                            (Some(upper), None) => upper,
+                            _ => letter,
+                        }
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut puzzle = Puzzle {
+        author: author.to_string(),
+        rows,
+        clues: Vec::new(),
+    };
+    puzzle.clues = puzzle
+        .entries()
+        .into_iter()
+        .map(|entry| Clue {
+            id: entry.id,
+            text: format!("TODO ({})", entry.answer),
+            source_line: 0,
+        })
+        .collect();
+
+    Ok(puzzle.to_string())
+}
+
+/// Writes the puzzle to `{dir}/{date}.txt`, the path the web player fetches
+/// it from, and returns that path. With `encryption` the file is encrypted
+/// in that format.
+pub fn write_puzzle(
+    grid: &CrosswordGrid,
+    author: &str,
+    dir: &str,
+    date: &str,
+    encryption: Option<encryption::Format>,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    check_date(date)?;
+    let mut content = puzzle_text(grid, author)?;
+    if let Some(format) = encryption {
+        content = encryption::encrypt_as(&content, format, Some(date))?;
+    }
+    fs::create_dir_all(dir)?;
+    let path = Path::new(dir).join(format!("{}.txt", date));
+    fs::write(&path, content)?;
+    Ok(path)
+}
+
+/// Checks that `date` has the `YYYY-MM-DD` shape puzzle file names use.
+pub fn check_date(date: &str) -> Result<(), String> {
+    let parts: Vec<&str> = date.split('-').collect();
+    let well_formed = parts.len() == 3
+        && [4, 2, 2].iter().zip(&parts).all(|(&len, part)| {
+            part.len() == len && part.chars().all(|ch| ch.is_ascii_digit())
+        });
+    let in_range = well_formed
+        && (1..=12).contains(&parts[1].parse::<u32>().unwrap_or(0))
+        && (1..=31).contains(&parts[2].parse::<u32>().unwrap_or(0));
+    if in_range {
+        Ok(())
+    } else {
+        Err(format!("'{}' is not a YYYY-MM-DD date", date))
+    }
+}
+
+/// Today's date in UTC as `YYYY-MM-DD`.
+pub fn today() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+    let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// The date `days` days after `date`, both `YYYY-MM-DD`.
+pub fn add_days(date: &str, days: usize) -> Result<String, String> {
+    let (year, month, day) = civil_from_days(day_number(date)? + days as i64);
+    Ok(format!("{:04}-{:02}-{:02}", year, month, day))
+}
+
+/// Days from `from` to `to`, both `YYYY-MM-DD`; negative if `to` comes
+/// first.
+pub fn days_between(from: &str, to: &str) -> Result<i64, String> {
+    Ok(day_number(to)? - day_number(from)?)
+}
+
+fn day_number(date: &str) -> Result<i64, String> {
+    check_date(date)?;
+    let parts: Vec<i64> = date.split('-').map(|part| part.parse().unwrap_or(0)).collect();
+    Ok(days_from_civil(parts[0], parts[1], parts[2]))
+}
+
+// Inverse of civil_from_days
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let shifted_month = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * shifted_month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+// Howard Hinnant's days-to-civil conversion for the proleptic Gregorian calendar
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
@@ -0,0 +1,1158 @@
+use crate::language::LanguageProfile;
+use crate::slots::Domains;
+use crate::symmetry::Symmetry;
+use crate::template::Template;
+use crate::{CrosswordGraph, Word};
+use itertools::Itertools;
+use rand::prelude::*;
+use std::collections::HashSet;
+use std::fs;
+use std::sync::Arc;
+
+// A word to place at a row and column, in a direction
+type Placement = (String, usize, usize, Direction);
+
+#[derive(Debug, Clone)]
+pub struct WordPlacement {
+    pub word: Word,
+    pub row: usize,
+    pub col: usize,
+    pub direction: Direction,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    Horizontal,
+    Vertical,
+}
+
+impl WordPlacement {
+    pub fn get_positions(&self) -> Vec<(usize, usize)> {
+        (0..self.word.len())
+            .map(|i| match self.direction {
+                Direction::Horizontal => (self.row, self.col + i),
+                Direction::Vertical => (self.row + i, self.col),
+            })
+            .collect()
+    }
+}
+
+/// Dictionary scores of a grid's entry words.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FillScore {
+    pub average: f64,
+    pub minimum: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct CrosswordGrid {
+    grid: Vec<Vec<char>>,
+    pub(crate) word_placements: Vec<WordPlacement>,
+    words_on_grid: HashSet<String>,
+    pub(crate) filled_cells: usize,
+    pub(crate) width: usize,
+    pub(crate) height: usize,
+    // Black squares, stored as '@' cells
+    blocks: usize,
+    symmetry: Symmetry,
+    // Shared so that cloning a grid during the search does not copy the graph
+    graph: Arc<CrosswordGraph>,
+    // Words kept out of the fill, such as recently published answers
+    avoided: Arc<HashSet<String>>,
+    // Save nearly complete grids to solvables/ and close_calls/
+    save_progress: bool,
+    // Started from a template: its blocks are final and no block is added
+    fixed_layout: bool,
+    // Slot candidates of the current cells, kept from the check of the last
+    // placement so that the placements after it reuse them
+    domains: Option<Arc<Domains>>,
+}
+
+impl CrosswordGrid {
+    pub fn new(graph: impl Into<Arc<CrosswordGraph>>) -> Self {
+        let graph = graph.into();
+        let width = graph.config().width;
+        let height = graph.config().height;
+        Self {
+            grid: vec![vec!['.'; width]; height],
+            word_placements: Vec::new(),
+            words_on_grid: HashSet::new(),
+            filled_cells: 0,
+            width,
+            height,
+            blocks: 0,
+            symmetry: Symmetry::None,
+            graph,
+            avoided: Arc::default(),
+            save_progress: false,
+            fixed_layout: false,
+            domains: None,
+        }
+    }
+
+    /// Starts from a template's blocks and pre-filled letters instead of an
+    /// empty grid. Template cells are never overwritten by the solver, and
+    /// no block is added: every run between blocks, whole lines included,
+    /// takes one word spanning it.
+    pub fn from_template(graph: impl Into<Arc<CrosswordGraph>>, template: &Template) -> Result<Self, String> {
+        let mut grid = Self::new(graph);
+        if template.width() != grid.width || template.height() != grid.height {
+            return Err(format!(
+                "template is {}x{} but the graph was built for {}x{}",
+                template.width(),
+                template.height(),
+                grid.width,
+                grid.height
+            ));
+        }
+
+        for (row, cells) in template.rows().iter().enumerate() {
+            for (col, &cell) in cells.iter().enumerate() {
+                match cell {
+                    '.' => {}
+                    '@' => grid.blocks += 1,
+                    _ => grid.filled_cells += 1,
+                }
+                grid.grid[row][col] = cell;
+            }
+        }
+        grid.fixed_layout = true;
+
+        Ok(grid)
+    }
+
+    /// The same grid with its blocks kept and every letter removed, filled
+    /// like a template.
+    pub fn block_layout(&self) -> Self {
+        let mut layout = Self::new(self.graph.clone());
+        for (row, cells) in self.grid.iter().enumerate() {
+            for (col, &cell) in cells.iter().enumerate() {
+                if cell == '@' {
+                    layout.grid[row][col] = '@';
+                    layout.blocks += 1;
+                }
+            }
+        }
+        layout.symmetry = self.symmetry;
+        layout.fixed_layout = true;
+        layout
+    }
+
+    /// Every across and down run of two or more letters, sorted.
+    pub fn entry_words(&self) -> Vec<String> {
+        let rows = (0..self.height).map(|row| self.get_row_state(row));
+        let cols = (0..self.width).map(|col| self.get_col_state(col));
+        let mut words: Vec<String> = rows
+            .chain(cols)
+            .flat_map(|line| {
+                line.split('@')
+                    .filter(|run| run.chars().count() >= 2)
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        words.sort();
+        words
+    }
+
+    /// Average and lowest dictionary score of the entry words, `None` while
+    /// the grid has no entry.
+    pub fn fill_score(&self) -> Option<FillScore> {
+        let scores: Vec<u32> = self.entry_words().iter().map(|word| self.graph.word_score(word)).collect();
+        let minimum = *scores.iter().min()?;
+        let average = scores.iter().map(|&score| score as f64).sum::<f64>() / scores.len() as f64;
+        Some(FillScore { average, minimum })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Rows of the grid, with `.` for empty cells.
+    pub fn cells(&self) -> &[Vec<char>] {
+        &self.grid
+    }
+
+    pub fn language(&self) -> &LanguageProfile {
+        &self.graph.config().language
+    }
+
+    pub fn graph(&self) -> &CrosswordGraph {
+        &self.graph
+    }
+
+    pub fn word_placements(&self) -> &[WordPlacement] {
+        &self.word_placements
+    }
+
+    pub fn filled_cells(&self) -> usize {
+        self.filled_cells
+    }
+
+    pub fn symmetry(&self) -> Symmetry {
+        self.symmetry
+    }
+
+    /// Requires blocks committed from now on to keep `symmetry`. Fails if
+    /// the current blocks already break it.
+    pub fn set_symmetry(&mut self, symmetry: Symmetry) -> Result<(), String> {
+        if symmetry == Symmetry::Diagonal && self.width != self.height {
+            return Err(format!("diagonal symmetry needs a square grid, not {}x{}", self.width, self.height));
+        }
+        self.symmetry = symmetry;
+        if self.is_symmetric() {
+            Ok(())
+        } else {
+            Err(format!("blocks do not have {} symmetry", symmetry))
+        }
+    }
+
+    /// Whether every block's mirror cell is also a block.
+    pub fn is_symmetric(&self) -> bool {
+        (0..self.height).all(|row| {
+            (0..self.width).all(|col| match self.symmetry.mirror(row, col, self.width, self.height) {
+                Some((r, c)) => (self.grid[row][col] == '@') == (self.grid[r][c] == '@'),
+                None => true,
+            })
+        })
+    }
+
+    fn cell_at(&self, row: usize, col: usize) -> Option<char> {
+        self.grid.get(row).and_then(|cells| cells.get(col)).copied()
+    }
+
+    /// Blocks a placement commits: the empty cells right before and after a
+    /// word that does not span its whole line, plus their mirror cells.
+    /// Returns `None` when the placement would break the grid's symmetry,
+    /// or, in a template's fixed layout, not span a whole run.
+    fn blocks_for_placement(&self, word: &str, row: usize, col: usize, direction: Direction) -> Option<Vec<(usize, usize)>> {
+        let length = word.chars().count();
+        let (before, after) = match direction {
+            Direction::Horizontal => (col.checked_sub(1).map(|c| (row, c)), (row, col + length)),
+            Direction::Vertical => (row.checked_sub(1).map(|r| (r, col)), (row + length, col)),
+        };
+
+        if self.fixed_layout {
+            let closed = before
+                .into_iter()
+                .chain(Some(after))
+                .all(|(r, c)| matches!(self.cell_at(r, c), None | Some('@')));
+            return closed.then(Vec::new);
+        }
+
+        let mut blocks: Vec<(usize, usize)> = before
+            .into_iter()
+            .chain(Some(after))
+            .filter(|&(r, c)| self.cell_at(r, c) == Some('.'))
+            .collect();
+
+        if self.symmetry == Symmetry::None {
+            return Some(blocks);
+        }
+
+        let letters: Vec<(usize, usize)> = (0..length)
+            .map(|i| match direction {
+                Direction::Horizontal => (row, col + i),
+                Direction::Vertical => (row + i, col),
+            })
+            .collect();
+
+        let mut mirrored = Vec::new();
+        for &(r, c) in &blocks {
+            let (mr, mc) = self.symmetry.mirror(r, c, self.width, self.height)?;
+            if letters.contains(&(mr, mc)) {
+                return None;
+            }
+            match self.cell_at(mr, mc)? {
+                '.' => mirrored.push((mr, mc)),
+                '@' => {}
+                _ => return None,
+            }
+        }
+        blocks.extend(mirrored);
+
+        // A letter may not land on a cell whose mirror is (or becomes) a block
+        for &(r, c) in &letters {
+            let mirror = self.symmetry.mirror(r, c, self.width, self.height)?;
+            if self.cell_at(mirror.0, mirror.1) == Some('@') || blocks.contains(&mirror) {
+                return None;
+            }
+        }
+
+        blocks.sort_unstable();
+        blocks.dedup();
+        Some(blocks)
+    }
+
+    /// Keeps `words` out of the entries the solver fills. Entries already
+    /// complete, such as a template's, are left alone.
+    pub fn set_avoided_words(&mut self, mut words: Arc<HashSet<String>>) {
+        let present: Vec<String> = self.complete_entries().filter(|word| words.contains(word)).collect();
+        if !present.is_empty() {
+            let words = Arc::make_mut(&mut words);
+            for word in &present {
+                words.remove(word);
+            }
+        }
+        self.avoided = words;
+        self.domains = None;
+    }
+
+    /// Saves nearly complete grids to `solvables/` or `close_calls/` in the
+    /// working directory as words are placed. Off by default.
+    pub fn set_save_progress(&mut self, save_progress: bool) {
+        self.save_progress = save_progress;
+    }
+
+    // Runs of two or more cells between blocks with every letter filled in
+    fn complete_entries(&self) -> impl Iterator<Item = String> + '_ {
+        self.entry_words().into_iter().filter(|word| !word.contains('.'))
+    }
+
+    // Whether `word` may not be placed: it is on the grid already or avoided
+    fn is_taken(&self, word: &str) -> bool {
+        self.words_on_grid.contains(word) || self.avoided.contains(word)
+    }
+
+    pub fn can_place_word(&self, word: &str, row: usize, col: usize, direction: Direction) -> bool {
+        // Early exit if word is already placed or avoided
+        if self.is_taken(word) {
+            return false;
+        }
+
+        // Letters and template blocks ('@') are fixed: a word may only cover
+        // empty cells or cells that already hold its letter
+        let word = Word::new(word);
+        match direction {
+            Direction::Horizontal => {
+                if row >= self.height || col + word.len() > self.width {
+                    return false;
+                }
+                for (i, &ch) in word.chars().iter().enumerate() {
+                    let cell = self.grid[row][col + i];
+                    if cell != '.' && cell != ch {
+                        return false;
+                    }
+                }
+            }
+            Direction::Vertical => {
+                if col >= self.width || row + word.len() > self.height {
+                    return false;
+                }
+                for (i, &ch) in word.chars().iter().enumerate() {
+                    let cell = self.grid[row + i][col];
+                    if cell != '.' && cell != ch {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    pub fn place_word(&mut self, word: &str, row: usize, col: usize, direction: Direction, verbose: bool) -> bool {
+        if !self.can_place_word(word, row, col, direction) {
+            if verbose {
+                println!("      ❌ Cannot place '{}' - conflicts with existing letters", word);
+            }
+            return false;
+        }
+
+        let blocks = match self.blocks_for_placement(word, row, col, direction) {
+            Some(blocks) => blocks,
+            None => {
+                if verbose && self.fixed_layout {
+                    println!("      ❌ Cannot place '{}' - does not fill a whole entry of the template", word);
+                } else if verbose {
+                    println!("      ❌ Cannot place '{}' - breaks {} symmetry", word, self.symmetry);
+                }
+                return false;
+            }
+        };
+
+        // Create backup
+        let old_grid = self.grid.clone();
+        let old_placements = self.word_placements.clone();
+        let old_words_on_grid = self.words_on_grid.clone();
+        let old_filled_cells = self.filled_cells;
+        let old_blocks = self.blocks;
+        let old_domains = self.domains.take();
+
+        // Place the word
+        let placement = WordPlacement {
+            word: Word::new(word),
+            row,
+            col,
+            direction,
+        };
+
+        let positions = placement.get_positions();
+        for ((r, c), &ch) in positions.iter().zip(placement.word.chars()) {
+            if self.grid[*r][*c] == '.' {
+                self.filled_cells += 1;
+            }
+            self.grid[*r][*c] = ch;
+        }
+
+        // Close the word off with blocks, mirrored for symmetry
+        for (r, c) in blocks {
+            self.grid[r][c] = '@';
+            self.blocks += 1;
+        }
+
+        self.word_placements.push(placement);
+        self.words_on_grid.insert(word.to_string());
+
+        // Check if we're close to completion and save grid
+        let empty_cells = self.open_cells() - self.filled_cells;
+        if self.save_progress && empty_cells <= (self.width / 2) {
+            if self.is_solvable_grid() {
+                self.save_grid_to_file("solvables/");
+            } else if empty_cells <= (self.width / 2).saturating_sub(1) {
+                self.save_grid_to_file("close_calls/");
+            }
+        }
+
+        // Final validation of the grid state, narrowing the domains the
+        // placement was checked against
+        let parent = old_domains.as_deref().map(|domains| (domains, word));
+        if let Some(domains) = self.checked_domains(parent, verbose) {
+            self.domains = Some(Arc::new(domains));
+            if verbose {
+                println!("      ✅ '{}' placed successfully and grid state validated", word);
+            }
+            true
+        } else {
+            if verbose {
+                println!("      ❌ '{}' placed but failed final grid state validation", word);
+            }
+            // Restore backup
+            self.grid = old_grid;
+            self.word_placements = old_placements;
+            self.words_on_grid = old_words_on_grid;
+            self.filled_cells = old_filled_cells;
+            self.blocks = old_blocks;
+            self.domains = old_domains;
+            false
+        }
+    }
+
+    pub fn get_row_state(&self, row: usize) -> String {
+        self.grid[row].iter().collect()
+    }
+
+    pub fn get_col_state(&self, col: usize) -> String {
+        (0..self.height).map(|row| self.grid[row][col]).collect()
+    }
+
+    pub fn validate_grid_state(&self, verbose: bool) -> bool {
+        self.checked_domains(None, verbose).is_some()
+    }
+
+    // Validates the grid state, returning its domains if it is valid. They
+    // are narrowed from `parent`, the domains of the grid before the word
+    // placed last, when given.
+    fn checked_domains(&self, parent: Option<(&Domains, &str)>, verbose: bool) -> Option<Domains> {
+        // Check all rows
+        for row in 0..self.height {
+            let row_state = self.get_row_state(row);
+            if !self.validate_row_column(&row_state) {
+                if verbose {
+                    println!("      🔍 Row {} validation failed: '{}'", row, row_state);
+                }
+                return None;
+            }
+        }
+
+        // Check all columns
+        for col in 0..self.width {
+            let col_state = self.get_col_state(col);
+            if !self.validate_row_column(&col_state) {
+                if verbose {
+                    println!("      🔍 Column {} validation failed: '{}'", col, col_state);
+                }
+                return None;
+            }
+        }
+
+        // Additional check: every filled cell must be able to form wordful liners
+        if !self.validate_cell_wordful_constraints(verbose) {
+            return None;
+        }
+
+        // Entries completed by crossing words must not be avoided either
+        if let Some(word) = self.complete_entries().find(|word| self.avoided.contains(word)) {
+            if verbose {
+                println!("      🔍 '{}' is an avoided word", word);
+            }
+            return None;
+        }
+
+        // Finally, every slot must keep a candidate that fits its crossings
+        let domains = self.build_domains(parent);
+        if domains.is_none() && verbose {
+            println!("      🔍 A slot has no candidate word left after propagation");
+        }
+        domains
+    }
+
+    /// Candidate words of every slot, see [`Domains`]. `None` when some slot
+    /// has no candidate left, so the grid cannot be completed. Grids made by
+    /// [`place_word`](Self::place_word) reuse the domains its check built.
+    pub fn domains(&self) -> Option<Arc<Domains>> {
+        match &self.domains {
+            Some(domains) => Some(Arc::clone(domains)),
+            None => self.build_domains(None).map(Arc::new),
+        }
+    }
+
+    fn build_domains(&self, parent: Option<(&Domains, &str)>) -> Option<Domains> {
+        let open_lines = |length| self.runs_are_entries(length);
+        let taken = |word: &str| self.is_taken(word);
+        match parent {
+            Some((parent, placed)) => parent.narrow(&self.grid, &self.graph, open_lines, taken, placed),
+            None => Domains::build(&self.grid, &self.graph, open_lines, taken),
+        }
+    }
+
+    fn validate_cell_wordful_constraints(&self, verbose: bool) -> bool {
+        for row in 0..self.height {
+            for col in 0..self.width {
+                if self.grid[row][col] != '.' {
+                    let row_state = self.get_row_state(row);
+                    if !self.can_form_wordful_liner(&row_state, verbose) {
+                        if verbose {
+                            println!("      🔍 Cell ({},{}) cannot form wordful horizontal liner: '{}'", row, col, row_state);
+                        }
+                        return false;
+                    }
+
+                    let col_state = self.get_col_state(col);
+                    if !self.can_form_wordful_liner(&col_state, verbose) {
+                        if verbose {
+                            println!("      🔍 Cell ({},{}) cannot form wordful vertical liner: '{}'", row, col, col_state);
+                        }
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    fn can_form_wordful_liner(&self, current_state: &str, _verbose: bool) -> bool {
+        if self.has_fixed_runs(current_state) {
+            return self.validate_blocked_line(current_state);
+        }
+
+        let empty_positions: Vec<usize> = current_state
+            .chars()
+            .enumerate()
+            .filter(|(_, c)| *c == '.')
+            .map(|(i, _)| i)
+            .collect();
+
+        if empty_positions.is_empty() {
+            // No empty cells - check if current state is valid
+            return self.graph.words().contains(current_state) || self.graph.liners().contains(current_state);
+        }
+
+        // Special case: if the entire state is empty, it's always valid
+        if empty_positions.len() == current_state.chars().count() {
+            return true;
+        }
+
+        // Try simple patterns
+        let mut pattern1 = current_state.chars().collect::<Vec<_>>();
+        for &pos in &empty_positions {
+            pattern1[pos] = '_';
+        }
+        let pattern1_str: String = pattern1.iter().collect();
+        if self.is_wordful_liner(&pattern1_str) {
+            return true;
+        }
+
+        let mut pattern2 = current_state.chars().collect::<Vec<_>>();
+        for &pos in &empty_positions {
+            pattern2[pos] = '@';
+        }
+        let pattern2_str: String = pattern2.iter().collect();
+        if self.is_wordful_liner(&pattern2_str) {
+            return true;
+        }
+
+        // Try more combinations if we have few empty positions
+        if empty_positions.len() <= 2 {
+            let replacements = ['_', '@'];
+            for combo in (0..empty_positions.len()).map(|_| replacements.iter()).multi_cartesian_product() {
+                let mut candidate = current_state.chars().collect::<Vec<_>>();
+                for (pos, replacement) in empty_positions.iter().zip(combo) {
+                    candidate[*pos] = *replacement;
+                }
+                let candidate_str: String = candidate.iter().collect();
+                if self.is_wordful_liner(&candidate_str) {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    fn is_wordful_liner(&self, liner_pattern: &str) -> bool {
+        if !self.graph.liners().contains(liner_pattern) {
+            return false;
+        }
+
+        let intermediaries = self.graph.get_intermediaries_for_liner(liner_pattern);
+        intermediaries
+            .iter()
+            .any(|intermediary| self.graph.query(intermediary).next().is_some())
+    }
+
+    fn validate_row_column(&self, current_state: &str) -> bool {
+        if self.has_fixed_runs(current_state) {
+            return self.validate_blocked_line(current_state);
+        }
+
+        let empty_positions: Vec<usize> = current_state
+            .chars()
+            .enumerate()
+            .filter(|(_, c)| *c == '.')
+            .map(|(i, _)| i)
+            .collect();
+
+        if empty_positions.is_empty() {
+            return self.graph.liners().contains(current_state) || self.graph.words().contains(current_state);
+        }
+
+        if empty_positions.len() == current_state.chars().count() {
+            return true;
+        }
+
+        // Try common patterns
+        let test_patterns = vec![
+            current_state.replace('.', "_"),
+            current_state.replace('.', "@"),
+        ];
+
+        for pattern in test_patterns {
+            if self.is_achievable_liner(&pattern) {
+                return true;
+            }
+        }
+
+        // Try more combinations for small number of empty positions
+        if empty_positions.len() <= 3 {
+            let replacements = ['_', '@'];
+            for combo in (0..empty_positions.len()).map(|_| replacements.iter()).multi_cartesian_product() {
+                let mut candidate = current_state.chars().collect::<Vec<_>>();
+                for (pos, replacement) in empty_positions.iter().zip(combo) {
+                    candidate[*pos] = *replacement;
+                }
+                let candidate_str: String = candidate.iter().collect();
+                if self.is_achievable_liner(&candidate_str) {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Checks a line with template blocks segment by segment: every run of
+    /// two or more cells between blocks must still be fillable by a word of
+    /// exactly that length.
+    fn validate_blocked_line(&self, line_state: &str) -> bool {
+        line_state.split('@').all(|segment| {
+            let length = segment.chars().count();
+            if length <= 1 {
+                return true;
+            }
+            if !segment.contains('.') {
+                return self.graph.words().contains(segment);
+            }
+            self.graph.query(&segment.replace('.', "_")).next().is_some()
+        })
+    }
+
+    /// Whether every run of `line` between blocks has to be filled by one
+    /// word: once the line holds a block, or whenever
+    /// [`runs_are_entries`](Self::runs_are_entries) holds for its length.
+    fn has_fixed_runs(&self, line: &str) -> bool {
+        line.contains('@') || self.runs_are_entries(line.chars().count())
+    }
+
+    /// Whether the runs of a line of `length` cells are single entries even
+    /// before it holds a block: in a template, without intermediaries, or
+    /// when the line is too short for two words and for any word shorter
+    /// than itself, so only a word spanning it fits.
+    fn runs_are_entries(&self, length: usize) -> bool {
+        let config = self.graph.config();
+        self.fixed_layout || !config.intermediaries || length <= config.min_word_length
+    }
+
+    fn is_achievable_liner(&self, liner_pattern: &str) -> bool {
+        self.is_wordful_liner(liner_pattern)
+    }
+
+    pub fn get_possible_placements(&self, rng: &mut StdRng) -> Vec<(String, usize, usize, Direction)> {
+        let (mut grid_size_placements, mut shorter_placements) = self.prioritized_placements();
+
+        // Randomize within each group
+        self.shuffle_by_score(&mut grid_size_placements, rng);
+        self.shuffle_by_score(&mut shorter_placements, rng);
+
+        if !grid_size_placements.is_empty() {
+            grid_size_placements
+        } else {
+            shorter_placements
+        }
+    }
+
+    /// Shuffles `placements` so that higher scoring words tend to come
+    /// first: each is keyed by `u^(1/weight)` for a uniform `u`, which puts a
+    /// word ahead of another with odds proportional to their weights.
+    /// Unscored dictionaries keep a plain shuffle.
+    fn shuffle_by_score(&self, placements: &mut Vec<Placement>, rng: &mut StdRng) {
+        if !self.graph.has_scores() {
+            placements.shuffle(rng);
+            return;
+        }
+        let mut keyed: Vec<(f64, Placement)> = placements
+            .drain(..)
+            .map(|placement| {
+                let weight = self.graph.word_score(&placement.0) as f64 + 1.0;
+                (rng.gen::<f64>().powf(1.0 / weight), placement)
+            })
+            .collect();
+        keyed.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+        placements.extend(keyed.into_iter().map(|(_, placement)| placement));
+    }
+
+    /// Placements for the most constrained entry only: the slot, or open
+    /// line, with the fewest placements left, ties broken by `rng`. Its words
+    /// come least constraining first, i.e. leaving the most words for the
+    /// entries crossing them. Words that leave a crossing slot without any
+    /// are dropped.
+    pub fn get_heuristic_placements(&self, rng: &mut StdRng) -> Vec<(String, usize, usize, Direction)> {
+        let (grid_size_placements, shorter_placements) = self.prioritized_placements();
+        let placements = if !grid_size_placements.is_empty() {
+            grid_size_placements
+        } else {
+            shorter_placements
+        };
+
+        // Group the placements by the entry they fill
+        let mut entries: Vec<((Direction, usize, usize), Vec<Placement>)> = Vec::new();
+        for placement in placements {
+            let key = self.entry_key(&placement);
+            match entries.iter_mut().find(|(entry, _)| *entry == key) {
+                Some((_, group)) => group.push(placement),
+                None => entries.push((key, vec![placement])),
+            }
+        }
+        entries.shuffle(rng);
+        let Some((_, mut placements)) = entries.into_iter().min_by_key(|(_, group)| group.len()) else {
+            return Vec::new();
+        };
+
+        placements.shuffle(rng);
+        let mut scored: Vec<(usize, Placement)> = placements
+            .into_iter()
+            .filter_map(|placement| Some((self.options_left(&placement)?, placement)))
+            .collect();
+        scored.sort_by_key(|(options, _)| std::cmp::Reverse(*options));
+        scored.into_iter().map(|(_, placement)| placement).collect()
+    }
+
+    /// Filtered placements, sorted and split into full-line words and
+    /// shorter ones.
+    fn prioritized_placements(&self) -> (Vec<Placement>, Vec<Placement>) {
+        let mut placements = Vec::new();
+        let Some(domains) = self.domains() else {
+            return (Vec::new(), Vec::new());
+        };
+
+        // Check each row for possible horizontal placements
+        for row in 0..self.height {
+            let row_state = self.get_row_state(row);
+            if row_state.contains('.') {
+                let row_placements = self.get_placements_for_line(&row_state, row, Direction::Horizontal);
+                placements.extend(row_placements);
+            }
+        }
+
+        // Check each column for possible vertical placements
+        for col in 0..self.width {
+            let col_state = self.get_col_state(col);
+            if col_state.contains('.') {
+                let col_placements = self.get_placements_for_line(&col_state, col, Direction::Vertical);
+                placements.extend(col_placements);
+            }
+        }
+
+        // The graph hands out words in hash set order, which changes from run
+        // to run; sort them so that the seed alone decides the shuffle
+        placements.sort();
+        placements.dedup();
+
+        // Filter out words already placed or avoided, words their slot's
+        // crossings rule out and placements that break symmetry
+        let valid_placements: Vec<_> = placements
+            .into_iter()
+            .filter(|(word, _, _, _)| !self.is_taken(word))
+            .filter(|(word, row, col, direction)| domains.allows(word, *row, *col, *direction))
+            .filter(|(word, row, col, direction)| {
+                self.symmetry == Symmetry::None || self.blocks_for_placement(word, *row, *col, *direction).is_some()
+            })
+            .collect();
+
+        // Prioritize by word length (full lines first)
+        valid_placements
+            .into_iter()
+            .partition(|(word, _, _, direction)| word.chars().count() == self.line_length(*direction))
+    }
+
+    /// Length of the rows, or columns, of the grid.
+    fn line_length(&self, direction: Direction) -> usize {
+        match direction {
+            Direction::Horizontal => self.width,
+            Direction::Vertical => self.height,
+        }
+    }
+
+    /// The entry a placement fills: its direction, line and the run of the
+    /// line between blocks it lies in.
+    fn entry_key(&self, (_, row, col, direction): &Placement) -> (Direction, usize, usize) {
+        let (line, position) = match direction {
+            Direction::Horizontal => (self.get_row_state(*row), *col),
+            Direction::Vertical => (self.get_col_state(*col), *row),
+        };
+        let run = line.chars().take(position).filter(|&ch| ch == '@').count();
+        match direction {
+            Direction::Horizontal => (*direction, *row, run),
+            Direction::Vertical => (*direction, *col, run),
+        }
+    }
+
+    /// How many words the runs crossing a placement's empty cells could
+    /// still take once it is made, or `None` when a crossing slot would have
+    /// none left.
+    fn options_left(&self, (word, row, col, direction): &Placement) -> Option<usize> {
+        let mut options = 0;
+        for (i, &letter) in Word::new(word).chars().iter().enumerate() {
+            let (r, c) = match direction {
+                Direction::Horizontal => (*row, col + i),
+                Direction::Vertical => (row + i, *col),
+            };
+            if self.grid[r][c] != '.' {
+                continue;
+            }
+
+            let (mut line, position): (Vec<char>, usize) = match direction {
+                Direction::Horizontal => (self.get_col_state(c).chars().collect(), r),
+                Direction::Vertical => (self.get_row_state(r).chars().collect(), c),
+            };
+            let is_slot = line.contains(&'@') || self.runs_are_entries(line.len());
+            line[position] = letter;
+            let start = line[..position].iter().rposition(|&ch| ch == '@').map_or(0, |block| block + 1);
+            let end = line[position..].iter().position(|&ch| ch == '@').map_or(line.len(), |block| position + block);
+            if end - start < 2 {
+                continue;
+            }
+
+            let pattern: String = line[start..end].iter().map(|&ch| if ch == '.' { '_' } else { ch }).collect();
+            let count = self.graph.count_words_for_pattern(&pattern);
+            if count == 0 && is_slot {
+                return None;
+            }
+            options += count;
+        }
+        Some(options)
+    }
+
+    fn get_placements_for_line(&self, line_state: &str, line_index: usize, direction: Direction) -> Vec<(String, usize, usize, Direction)> {
+        if self.has_fixed_runs(line_state) {
+            return self.get_placements_for_blocked_line(line_state, line_index, direction);
+        }
+
+        let mut placements = Vec::new();
+        let empty_positions: Vec<usize> = line_state
+            .chars()
+            .enumerate()
+            .filter(|(_, c)| *c == '.')
+            .map(|(i, _)| i)
+            .collect();
+
+        if empty_positions.is_empty() {
+            return placements;
+        }
+
+        let possible_liners = self.generate_liner_patterns(line_state);
+
+        for liner_pattern in possible_liners {
+            if self.graph.liners().contains(&liner_pattern) {
+                let intermediaries = self.graph.get_intermediaries_for_liner(&liner_pattern);
+
+                for intermediary in intermediaries {
+                    for word in self.graph.query(&intermediary) {
+                        let word_placements = self.find_word_positions_in_liner(&Word::new(word), &liner_pattern, line_state);
+
+                        for start_pos in word_placements {
+                            match direction {
+                                Direction::Horizontal => {
+                                    placements.push((word.to_string(), line_index, start_pos, Direction::Horizontal));
+                                }
+                                Direction::Vertical => {
+                                    placements.push((word.to_string(), start_pos, line_index, Direction::Vertical));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        placements
+    }
+
+    /// Placements for a line with template blocks: each open segment takes a
+    /// word spanning it from block to block.
+    fn get_placements_for_blocked_line(&self, line_state: &str, line_index: usize, direction: Direction) -> Vec<(String, usize, usize, Direction)> {
+        let mut placements = Vec::new();
+        let mut start = 0;
+
+        for segment in line_state.split('@') {
+            let length = segment.chars().count();
+            if length > 1 && segment.contains('.') {
+                let pattern = segment.replace('.', "_");
+                for word in self.graph.query(&pattern) {
+                    match direction {
+                        Direction::Horizontal => placements.push((word.to_string(), line_index, start, Direction::Horizontal)),
+                        Direction::Vertical => placements.push((word.to_string(), start, line_index, Direction::Vertical)),
+                    }
+                }
+            }
+            start += length + 1;
+        }
+
+        placements
+    }
+
+    fn generate_liner_patterns(&self, line_state: &str) -> Vec<String> {
+        let empty_positions: Vec<usize> = line_state
+            .chars()
+            .enumerate()
+            .filter(|(_, c)| *c == '.')
+            .map(|(i, _)| i)
+            .collect();
+
+        if empty_positions.is_empty() {
+            return vec![line_state.to_string()];
+        }
+
+        let mut patterns = Vec::new();
+
+        if empty_positions.len() <= 3 {
+            let replacements = ['_', '@'];
+            for combo in (0..empty_positions.len()).map(|_| replacements.iter()).multi_cartesian_product() {
+                let mut pattern = line_state.chars().collect::<Vec<_>>();
+                for (pos, replacement) in empty_positions.iter().zip(combo) {
+                    pattern[*pos] = *replacement;
+                }
+                patterns.push(pattern.iter().collect());
+            }
+        } else {
+            // For larger number of empty positions, try only common patterns
+            patterns.push(line_state.replace('.', "_"));
+            patterns.push(line_state.replace('.', "@"));
+
+            if empty_positions.len() >= 2 {
+                let mid = empty_positions.len() / 2;
+                let mut pattern3 = line_state.chars().collect::<Vec<_>>();
+                for (i, &pos) in empty_positions.iter().enumerate() {
+                    pattern3[pos] = if i < mid { '_' } else { '@' };
+                }
+                patterns.push(pattern3.iter().collect());
+
+                let mut pattern4 = line_state.chars().collect::<Vec<_>>();
+                for (i, &pos) in empty_positions.iter().enumerate() {
+                    pattern4[pos] = if i < mid { '@' } else { '_' };
+                }
+                patterns.push(pattern4.iter().collect());
+            }
+        }
+
+        // Filter to only return patterns that exist in our liner set
+        patterns
+            .into_iter()
+            .filter(|pattern| self.graph.liners().contains(pattern))
+            .collect()
+    }
+
+    fn find_word_positions_in_liner(&self, word: &Word, liner_pattern: &str, line_state: &str) -> Vec<usize> {
+        let mut valid_positions = Vec::new();
+
+        let liner_chars = Word::new(liner_pattern);
+        let line_chars = Word::new(line_state);
+        if word.len() > line_chars.len() || liner_chars.len() != line_chars.len() {
+            return valid_positions;
+        }
+
+        for start_pos in 0..=(line_chars.len() - word.len()) {
+            let mut can_place = true;
+
+            for (i, &ch) in word.chars().iter().enumerate() {
+                let liner_pos = start_pos + i;
+                let liner_char = liner_chars.chars()[liner_pos];
+                let line_char = line_chars.chars()[liner_pos];
+
+                if liner_char == '@'
+                    || (liner_char != '_' && liner_char != ch)
+                    || (line_char != '.' && line_char != ch)
+                {
+                    can_place = false;
+                    break;
+                }
+            }
+
+            if can_place {
+                valid_positions.push(start_pos);
+            }
+        }
+
+        valid_positions
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.filled_cells == self.open_cells()
+    }
+
+    pub fn total_cells(&self) -> usize {
+        self.width * self.height
+    }
+
+    /// Cells that need a letter, i.e. everything except black squares.
+    pub fn open_cells(&self) -> usize {
+        self.total_cells() - self.blocks
+    }
+
+    pub fn print_grid(&self) {
+        println!("📋 Current Grid:");
+        print!("   ");
+        for i in 0..self.width {
+            print!("{} ", i);
+        }
+        println!();
+
+        for (i, row) in self.grid.iter().enumerate() {
+            print!(" {} ", i);
+            for &cell in row {
+                print!("{} ", if cell == '.' { '·' } else { cell });
+            }
+            println!();
+        }
+
+        let total_cells = self.open_cells();
+        let fill_percentage = (self.filled_cells as f64 / total_cells as f64) * 100.0;
+        println!("📈 Fill progress: {}/{} cells ({:.1}%)", self.filled_cells, total_cells, fill_percentage);
+        println!();
+    }
+
+    pub fn is_solvable_grid(&self) -> bool {
+        // Check if all rows and columns can still lead to valid liners
+        for row in 0..self.height {
+            if !self.is_solvable_row(row) {
+                return false;
+            }
+        }
+        for col in 0..self.width {
+            if !self.is_solvable_column(col) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn is_solvable_row(&self, row: usize) -> bool {
+        let row_state = self.get_row_state(row);
+        self.is_solvable_line_state(&row_state)
+    }
+
+    fn is_solvable_column(&self, col: usize) -> bool {
+        let col_state = self.get_col_state(col);
+        self.is_solvable_line_state(&col_state)
+    }
+
+    fn is_solvable_line_state(&self, line_state: &str) -> bool {
+        let words: Vec<&str> = line_state.split(['.', '@']).collect();
+        words.iter().all(|&word| {
+            word.is_empty() || (self.graph.words().contains(word) && word.chars().count() >= self.graph.config().min_word_length)
+        })
+    }
+
+    pub fn save_grid_to_file(&self, dirname: &str) {
+        let signature = self.get_grid_state_signature();
+        let filename = format!("{}/grid_{}x{}_{}_empty_{}.txt", dirname, self.width, self.height, signature.matches('-').count(), signature);
+        
+        if let Err(e) = fs::create_dir_all(dirname) {
+            eprintln!("Error creating directory: {}", e);
+            return;
+        }
+
+        let mut content = String::new();
+        content.push_str("Grid saved\n");
+        content.push_str(&format!("Filled cells: {}/{}\n", self.filled_cells, self.open_cells()));
+        content.push_str(&format!("Words placed: {}\n", self.word_placements.len()));
+        content.push_str(&format!("Symmetry: {}\n\n", self.symmetry));
+
+        content.push_str("Grid:\n");
+        content.push_str("   ");
+        for i in 0..self.width {
+            content.push_str(&format!("{} ", i));
+        }
+        content.push('\n');
+
+        let language = &self.graph.config().language;
+        for (i, row) in self.grid.iter().enumerate() {
+            content.push_str(&format!(" {} ", i));
+            for &cell in row {
+                if cell == '.' {
+                    content.push_str("· ");
+                } else {
+                    content.push_str(&format!("{} ", language.locale.to_uppercase(&language.expand_digraphs(&cell.to_string()))));
+                }
+            }
+            content.push('\n');
+        }
+
+        content.push_str("\nPlaced words:\n");
+        for (i, word) in self.word_placements.iter().enumerate() {
+            content.push_str(&format!("{:2}. '{}' at ({},{}) {:?}\n", i + 1, word.word, word.row, word.col, word.direction));
+        }
+
+        let unique_words: Vec<String> = self.words_on_grid.iter().cloned().collect();
+        content.push_str(&format!("\nUnique words placed ({}):\n", unique_words.len()));
+        for (i, word) in unique_words.iter().enumerate() {
+            content.push_str(&format!("{:2}. {}\n", i + 1, word));
+        }
+
+        if let Err(e) = fs::write(&filename, content) {
+            eprintln!("Error saving grid to file: {}", e);
+        }
+    }
+
+    pub fn get_grid_state_signature(&self) -> String {
+        self.grid
+            .iter()
+            .map(|row| row.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("")
+            .replace('.', "-")
+    }
+}
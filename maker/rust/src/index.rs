@@ -0,0 +1,125 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::Word;
+
+/// Words grouped by length, with one bitset of word ids per (position,
+/// letter) pair in each group. A pattern such as `_a__e` is answered by
+/// ANDing the bitsets of its letters, so no masked copy of any word has to
+/// be stored.
+#[derive(Debug, Clone, Default)]
+pub struct PatternIndex {
+    groups: HashMap<usize, LengthGroup>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct LengthGroup {
+    // Sorted, so that queries return words in a stable order
+    words: Vec<String>,
+    letters: HashMap<(usize, char), Bitset>,
+}
+
+impl PatternIndex {
+    pub fn new(words: &HashSet<String>) -> Self {
+        let mut by_length: HashMap<usize, Vec<&String>> = HashMap::new();
+        for word in words {
+            by_length.entry(word.chars().count()).or_default().push(word);
+        }
+
+        let groups = by_length
+            .into_iter()
+            .map(|(length, mut words)| {
+                words.sort();
+                let mut letters: HashMap<(usize, char), Bitset> = HashMap::new();
+                for (id, word) in words.iter().enumerate() {
+                    for (position, letter) in word.chars().enumerate() {
+                        letters
+                            .entry((position, letter))
+                            .or_insert_with(|| Bitset::new(words.len()))
+                            .insert(id);
+                    }
+                }
+                let words = words.into_iter().cloned().collect();
+                (length, LengthGroup { words, letters })
+            })
+            .collect();
+
+        Self { groups }
+    }
+
+    /// Words matching `pattern`, where `_` matches any letter, in sorted
+    /// order. A pattern without `_` matches the word itself.
+    pub fn query(&self, pattern: &str) -> impl Iterator<Item = &str> {
+        let pattern = Word::new(pattern);
+        let matches = self.groups.get(&pattern.len()).map(|group| {
+            let mut fixed = pattern.chars().iter().enumerate().filter(|(_, &letter)| letter != '_');
+            let ids = match fixed.next() {
+                None => Bitset::full(group.words.len()),
+                Some((position, &letter)) => {
+                    let mut ids = group.letters.get(&(position, letter)).cloned().unwrap_or_else(|| Bitset::new(0));
+                    for (position, &letter) in fixed {
+                        match group.letters.get(&(position, letter)) {
+                            Some(other) => ids.and(other),
+                            None => ids = Bitset::new(0),
+                        }
+                    }
+                    ids
+                }
+            };
+            (group, ids)
+        });
+
+        matches
+            .into_iter()
+            .flat_map(|(group, ids)| ids.into_iter().map(move |id| group.words[id].as_str()))
+    }
+
+    /// Number of (length, position, letter) bitsets.
+    pub fn len(&self) -> usize {
+        self.groups.values().map(|group| group.letters.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Bitset(Vec<u64>);
+
+impl Bitset {
+    fn new(len: usize) -> Self {
+        Self(vec![0; len.div_ceil(64)])
+    }
+
+    fn full(len: usize) -> Self {
+        let mut bits = vec![u64::MAX; len / 64];
+        if !len.is_multiple_of(64) {
+            bits.push((1 << (len % 64)) - 1);
+        }
+        Self(bits)
+    }
+
+    fn insert(&mut self, id: usize) {
+        self.0[id / 64] |= 1 << (id % 64);
+    }
+
+    fn and(&mut self, other: &Bitset) {
+        self.0.truncate(other.0.len());
+        for (bits, other) in self.0.iter_mut().zip(&other.0) {
+            *bits &= other;
+        }
+    }
+
+    fn into_iter(self) -> impl Iterator<Item = usize> {
+        self.0.into_iter().enumerate().flat_map(|(block, mut bits)| {
+            std::iter::from_fn(move || {
+                if bits == 0 {
+                    return None;
+                }
+                let bit = bits.trailing_zeros() as usize;
+                bits &= bits - 1;
+                Some(block * 64 + bit)
+            })
+        })
+    }
+}
@@ -0,0 +1,194 @@
+use std::fs;
+use std::path::Path;
+
+use crate::locale::Locale;
+
+pub const TURKISH_ALPHABET: &str = "abcçdefgğhıijklmnoöprsştuüvyz";
+pub const ENGLISH_ALPHABET: &str = "abcdefghijklmnopqrstuvwxyz";
+
+/// Everything the graph and maker need to know about a puzzle language.
+///
+/// Built-in profiles exist for `tr` and `en`, matching the codes used in
+/// `localization/*.json`. Other languages can be described in a profile file:
+///
+/// ```text
+/// # Dutch
+/// code = nl
+/// alphabet = abcdefghijklmnopqrstuvwxyzĳ
+/// case = en
+/// dictionary = ../dutch_words.txt
+/// digraph = ij ĳ
+/// ```
+///
+/// A digraph is a letter pair that occupies a single grid cell. It is folded
+/// into its cell character when words are loaded and expanded back when a
+/// grid is printed or saved. Exported puzzles keep the cell character, as
+/// the site's format has one character per cell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageProfile {
+    pub code: String,
+    pub alphabet: String,
+    pub locale: Locale,
+    pub digraphs: Vec<(String, char)>,
+    pub dictionary: String,
+}
+
+impl LanguageProfile {
+    pub fn turkish() -> Self {
+        Self {
+            code: "tr".to_string(),
+            alphabet: TURKISH_ALPHABET.to_string(),
+            locale: Locale::Turkish,
+            digraphs: Vec::new(),
+            dictionary: "../turkish_words.txt".to_string(),
+        }
+    }
+
+    pub fn english() -> Self {
+        Self {
+            code: "en".to_string(),
+            alphabet: ENGLISH_ALPHABET.to_string(),
+            locale: Locale::English,
+            digraphs: Vec::new(),
+            dictionary: "../english_words.txt".to_string(),
+        }
+    }
+
+    /// Returns the built-in profile for a language code, if there is one.
+    pub fn builtin(code: &str) -> Option<Self> {
+        match Locale::from_code(code)? {
+            Locale::Turkish => Some(Self::turkish()),
+            Locale::English => Some(Self::english()),
+        }
+    }
+
+    /// Resolves a `--lang` argument: a built-in language code or the path
+    /// to a profile file.
+    pub fn resolve(code_or_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        if let Some(profile) = Self::builtin(code_or_path) {
+            return Ok(profile);
+        }
+        if Path::new(code_or_path).is_file() {
+            return Self::load(code_or_path);
+        }
+        Err(format!(
+            "unknown language '{}': expected tr, en or a profile file",
+            code_or_path
+        )
+        .into())
+    }
+
+    /// The dictionary to read: `input` if given, otherwise the profile's own,
+    /// which has to exist. Only the Turkish one ships with the repo.
+    pub fn dictionary_or(&self, input: Option<&str>) -> Result<String, String> {
+        if let Some(input) = input {
+            return Ok(input.to_string());
+        }
+        if !Path::new(&self.dictionary).is_file() {
+            return Err(format!(
+                "no dictionary for '{}' at {}: pass one with --input",
+                self.code, self.dictionary
+            ));
+        }
+        Ok(self.dictionary.clone())
+    }
+
+    pub fn load(filename: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(filename)?;
+        Self::parse(&content).map_err(|e| format!("{}: {}", filename, e).into())
+    }
+
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut code = None;
+        let mut alphabet = None;
+        let mut locale = Locale::English;
+        let mut digraphs = Vec::new();
+        let mut dictionary = None;
+
+        for (index, line) in content.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .map(|(k, v)| (k.trim(), v.trim()))
+                .ok_or_else(|| format!("line {}: expected 'key = value'", line_number))?;
+
+            match key {
+                "code" => code = Some(value.to_string()),
+                "alphabet" => alphabet = Some(value.to_string()),
+                "case" => {
+                    locale = value
+                        .parse()
+                        .map_err(|e| format!("line {}: {}", line_number, e))?
+                }
+                "dictionary" => dictionary = Some(value.to_string()),
+                "digraph" => {
+                    let parts: Vec<&str> = value.split_whitespace().collect();
+                    let cell = match parts.as_slice() {
+                        [letters, cell] if letters.chars().count() > 1 => {
+                            let mut cell_chars = cell.chars();
+                            match (cell_chars.next(), cell_chars.next()) {
+                                (Some(ch), None) => Some((letters.to_string(), ch)),
+                                _ => None,
+                            }
+                        }
+                        _ => None,
+                    };
+                    digraphs.push(cell.ok_or_else(|| {
+                        format!("line {}: expected 'digraph = <letters> <cell>'", line_number)
+                    })?);
+                }
+                _ => return Err(format!("line {}: unknown key '{}'", line_number, key)),
+            }
+        }
+
+        let code = code.ok_or("missing 'code'")?;
+        let alphabet = alphabet.ok_or("missing 'alphabet'")?;
+        let dictionary = dictionary.unwrap_or_else(|| format!("../{}_words.txt", code));
+
+        Ok(Self {
+            code,
+            alphabet,
+            locale,
+            digraphs,
+            dictionary,
+        })
+    }
+
+    pub fn is_valid_word(&self, word: &str) -> bool {
+        word.chars().all(|c| self.alphabet.contains(c))
+    }
+
+    /// Replaces every digraph with the character of the cell it occupies.
+    pub fn fold_digraphs(&self, word: &str) -> String {
+        self.digraphs
+            .iter()
+            .fold(word.to_string(), |acc, (letters, cell)| {
+                acc.replace(letters.as_str(), &cell.to_string())
+            })
+    }
+
+    /// Expands digraph cells back into their letters.
+    pub fn expand_digraphs(&self, word: &str) -> String {
+        self.digraphs
+            .iter()
+            .fold(word.to_string(), |acc, (letters, cell)| {
+                acc.replace(*cell, letters)
+            })
+    }
+
+    /// Lowercases a raw dictionary entry and folds its digraphs.
+    pub fn normalize_word(&self, text: &str) -> String {
+        self.fold_digraphs(&self.locale.normalize_word(text))
+    }
+}
+
+impl Default for LanguageProfile {
+    fn default() -> Self {
+        Self::turkish()
+    }
+}
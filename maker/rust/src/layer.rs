@@ -0,0 +1,89 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// What a word list does to the words loaded before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LayerKind {
+    /// The main dictionary, loaded first.
+    Base,
+    /// Extra words, such as new slang, added on top.
+    Add,
+    /// Words to keep out of grids: offensive, trademarked or recently used.
+    Remove,
+}
+
+impl LayerKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LayerKind::Base => "base",
+            LayerKind::Add => "add",
+            LayerKind::Remove => "remove",
+        }
+    }
+}
+
+/// One word list of the ordered stack a graph is built from. Later layers
+/// win: a word removed by one layer comes back if a later layer adds it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordLayer {
+    pub kind: LayerKind,
+    pub path: String,
+    /// Name the layer is reported under, the path by default.
+    pub label: String,
+}
+
+impl WordLayer {
+    pub fn new(kind: LayerKind, path: &str) -> Self {
+        Self {
+            kind,
+            path: path.to_string(),
+            label: path.to_string(),
+        }
+    }
+
+    pub fn base(path: &str) -> Self {
+        Self::new(LayerKind::Base, path)
+    }
+
+    pub fn with_label(mut self, label: &str) -> Self {
+        self.label = label.to_string();
+        self
+    }
+}
+
+/// Parses `add:FILE` or `remove:FILE`, optionally labelled as
+/// `add:LABEL=FILE`.
+impl FromStr for WordLayer {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (kind, source) = match text.split_once(':') {
+            Some(("add", source)) => (LayerKind::Add, source),
+            Some(("remove", source)) => (LayerKind::Remove, source),
+            _ => return Err(format!("invalid word layer '{}': expected add:FILE or remove:FILE", text)),
+        };
+        let layer = match source.split_once('=') {
+            Some((label, path)) => WordLayer::new(kind, path).with_label(label),
+            None => WordLayer::new(kind, source),
+        };
+        if layer.path.is_empty() {
+            return Err(format!("invalid word layer '{}': missing file", text));
+        }
+        Ok(layer)
+    }
+}
+
+impl fmt::Display for WordLayer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({})", self.label, self.kind.name())
+    }
+}
+
+/// What loading a layer changed in the graph's words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LayerCounts {
+    /// Words the layer brought in that no earlier layer had.
+    pub added: usize,
+    /// Words the layer took out.
+    pub removed: usize,
+}
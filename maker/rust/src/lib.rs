@@ -1,20 +1,113 @@
 use std::collections::{HashMap, HashSet};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::fs;
 use itertools::Itertools;
 use rayon::prelude::*;
 
-// Configuration constants
-pub const GRID_SIZE: usize = 5;
-pub const MIN_WORD_LENGTH: usize = 5;
-pub const TURKISH_ALPHABET: &str = "abcdefghijklmnopqrstuvwxyzçğıöşü";
+pub mod archive;
+pub mod cache;
+pub mod check;
+pub mod encryption;
+pub mod export;
+pub mod grid;
+pub mod index;
+pub mod language;
+pub mod layer;
+pub mod locale;
+pub mod puzzle;
+pub mod slots;
+pub mod solver;
+pub mod symmetry;
+pub mod template;
+pub mod theme;
+pub mod word;
+
+pub use archive::Archive;
+pub use grid::{CrosswordGrid, Direction, FillScore, WordPlacement};
+pub use index::PatternIndex;
+pub use language::LanguageProfile;
+pub use layer::{LayerCounts, LayerKind, WordLayer};
+pub use locale::Locale;
+pub use puzzle::Puzzle;
+pub use slots::{Domains, Slot};
+pub use solver::{CancellationToken, CrosswordSolver, Enumeration, Portfolio, SolveOutcome, Uniqueness};
+pub use symmetry::Symmetry;
+pub use template::Template;
+pub use theme::{SeedWord, Theme};
+pub use word::Word;
+
+// Default configuration
+pub const DEFAULT_GRID_SIZE: usize = 5;
+pub const DEFAULT_MIN_WORD_LENGTH: usize = 5;
+/// Score of words a dictionary lists without one (`word;score` lines set it).
+pub const DEFAULT_WORD_SCORE: u32 = 50;
+
+/// Grid shape and word constraints a graph is built for.
+///
+/// Rows are `width` cells long and columns `height`, and liners are
+/// generated at both lengths, so grids need not be square.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphConfig {
+    pub width: usize,
+    pub height: usize,
+    pub min_word_length: usize,
+    pub language: LanguageProfile,
+    /// Enumerates every word's intermediaries and builds liners from them,
+    /// so that a line without blocks may hold several words. Without them
+    /// every run between blocks, whole lines included, takes exactly one
+    /// word, and graphs of long words build far faster and smaller.
+    pub intermediaries: bool,
+}
+
+impl GraphConfig {
+    pub fn square(size: usize, min_word_length: usize) -> Self {
+        Self::rectangle(size, size, min_word_length)
+    }
+
+    pub fn rectangle(width: usize, height: usize, min_word_length: usize) -> Self {
+        Self {
+            width,
+            height,
+            min_word_length,
+            ..Self::default()
+        }
+    }
+
+    /// Lengths of the rows and columns, once each.
+    pub fn line_lengths(&self) -> Vec<usize> {
+        if self.width == self.height {
+            vec![self.width]
+        } else {
+            vec![self.width, self.height]
+        }
+    }
+
+    /// Longest word that fits in any line of the grid.
+    pub fn max_word_length(&self) -> usize {
+        self.width.max(self.height)
+    }
+}
+
+impl Default for GraphConfig {
+    fn default() -> Self {
+        Self {
+            width: DEFAULT_GRID_SIZE,
+            height: DEFAULT_GRID_SIZE,
+            min_word_length: DEFAULT_MIN_WORD_LENGTH,
+            language: LanguageProfile::default(),
+            intermediaries: true,
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct CrosswordGraph {
-    // Dictionary mapping intermediary patterns to words that match them
-    intermediary_to_words: HashMap<String, HashSet<String>>,
+    config: GraphConfig,
+    // Positional index of the words, answering pattern queries
+    index: PatternIndex,
     // Set of all valid words
     words: HashSet<String>,
+    // Scores given by the dictionary; other words score DEFAULT_WORD_SCORE
+    scores: HashMap<String, u32>,
     // Set of all words that can be checked against (includes longer words)
     words_that_can_be_checked_against: HashSet<String>,
     // Set of all real intermediaries (those that actually occur)
@@ -23,60 +116,130 @@ pub struct CrosswordGraph {
     liner_to_intermediaries: HashMap<String, HashSet<String>>,
     // Set of all valid liners
     liners: HashSet<String>,
+    // Running hash of every dictionary loaded, used to key the cache
+    source_hash: u64,
+}
+
+impl Default for CrosswordGraph {
+    fn default() -> Self {
+        Self::new(GraphConfig::default())
+    }
 }
 
 impl CrosswordGraph {
-    pub fn new() -> Self {
+    pub fn new(config: GraphConfig) -> Self {
         Self {
-            intermediary_to_words: HashMap::new(),
+            config,
+            index: PatternIndex::default(),
             words: HashSet::new(),
+            scores: HashMap::new(),
             words_that_can_be_checked_against: HashSet::new(),
             real_intermediaries: HashSet::new(),
             liner_to_intermediaries: HashMap::new(),
             liners: HashSet::new(),
+            source_hash: cache::Fnv64::new().finish(),
         }
     }
 
+    /// Loads `dictionary` and runs every graph building step.
+    pub fn build(config: GraphConfig, dictionary: &str, verbose: bool, parallel: bool) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::build_layers(config, &[WordLayer::base(dictionary)], verbose, parallel)
+    }
+
+    /// Loads `layers` in order and runs every graph building step, so that
+    /// removed words never reach the intermediaries or liners.
+    pub fn build_layers(config: GraphConfig, layers: &[WordLayer], verbose: bool, parallel: bool) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut graph = Self::new(config);
+        for layer in layers {
+            graph.load_layer(layer, verbose)?;
+        }
+        if graph.config.intermediaries {
+            graph.find_real_intermediaries(verbose, parallel);
+        }
+        graph.build_graph(verbose);
+        graph.generate_liners(verbose);
+        Ok(graph)
+    }
+
     pub fn load_words(&mut self, filename: &str, verbose: bool) -> Result<(), Box<dyn std::error::Error>> {
+        self.load_layer(&WordLayer::base(filename), verbose)?;
+        Ok(())
+    }
+
+    /// Adds the words of `layer`, or takes them out for a removal layer.
+    /// Removed words stay in `words_that_can_be_checked_against`: they are
+    /// kept out of grids, but are still words.
+    pub fn load_layer(&mut self, layer: &WordLayer, verbose: bool) -> Result<LayerCounts, Box<dyn std::error::Error>> {
         if verbose {
-            println!("Loading words from {}...", filename);
-        }
-        
-        let file = File::open(filename)?;
-        let reader = BufReader::new(file);
-        
-        for line in reader.lines() {
-            let line = line?;
-            let word = line.trim().to_lowercase().replace(" ", "");
-            
-            // Filter words: length between MIN_WORD_LENGTH and GRID_SIZE
-            if word.len() >= MIN_WORD_LENGTH && word.len() <= GRID_SIZE {
-                if Self::is_valid_word(&word) {
-                    self.words.insert(word.clone());
+            println!("Loading words from {}...", layer);
+        }
+        
+        let content = fs::read_to_string(&layer.path)?;
+        self.hash_source(layer.kind.name().as_bytes());
+        self.hash_source(content.as_bytes());
+        
+        let mut counts = LayerCounts::default();
+        for (number, line) in content.lines().enumerate() {
+            // Lines are `word` or `word;score`
+            let (line, score) = match line.split_once(';') {
+                Some((word, _)) if layer.kind == LayerKind::Remove => (word, None),
+                Some((word, score)) => {
+                    let score = score
+                        .trim()
+                        .parse::<u32>()
+                        .map_err(|_| format!("{}: line {}: invalid score {:?}", layer.path, number + 1, score.trim()))?;
+                    (word, Some(score))
+                }
+                None => (line, None),
+            };
+            let word = self.config.language.normalize_word(line);
+            if !self.is_valid_word(&word) {
+                continue;
+            }
+
+            if layer.kind == LayerKind::Remove {
+                if self.words.remove(&word) {
+                    self.scores.remove(&word);
+                    counts.removed += 1;
                 }
+                continue;
             }
             
-            // Add to extended word set if valid
-            if Self::is_valid_word(&word) {
-                self.words_that_can_be_checked_against.insert(word);
+            // Filter words: length between min_word_length and the longest line
+            let letter_count = word.chars().count();
+            if letter_count >= self.config.min_word_length && letter_count <= self.config.max_word_length() {
+                if let Some(score) = score {
+                    // Keep the best score of words that normalize alike
+                    let best = self.scores.entry(word.clone()).or_insert(score);
+                    *best = (*best).max(score);
+                }
+                if self.words.insert(word.clone()) {
+                    counts.added += 1;
+                }
             }
+            
+            // Add to extended word set
+            self.words_that_can_be_checked_against.insert(word);
         }
         
         if verbose {
-            println!("Loaded {} valid words", self.words.len());
+            match layer.kind {
+                LayerKind::Remove => println!("Removed {} words, {} valid words left", counts.removed, self.words.len()),
+                _ => println!("Added {} words, {} valid words in total", counts.added, self.words.len()),
+            }
         }
         
-        Ok(())
+        Ok(counts)
     }
 
-    fn is_valid_word(word: &str) -> bool {
-        word.chars().all(|c| TURKISH_ALPHABET.contains(c))
+    pub fn is_valid_word(&self, word: &str) -> bool {
+        self.config.language.is_valid_word(word)
     }
 
     pub fn generate_intermediaries_for_word(&self, word: &str) -> HashSet<String> {
         let mut intermediaries = HashSet::new();
+        let word = Word::new(word);
         let word_len = word.len();
-        let word_chars: Vec<char> = word.chars().collect();
         
         // Generate all possible combinations with underscores
         // For each position, we can either have the letter or underscore
@@ -90,8 +253,8 @@ impl CrosswordGraph {
                     intermediary.push('_');
                     has_underscore = true;
                 } else {  // Otherwise use the actual letter
-                    if j < word_chars.len() {
-                        intermediary.push(word_chars[j]);
+                    if let Some(ch) = word.char_at(j) {
+                        intermediary.push(ch);
                         has_letter = true;
                     }
                 }
@@ -131,7 +294,7 @@ impl CrosswordGraph {
         }
         
         // Add pure underscore patterns as wildcard intermediaries
-        for length in MIN_WORD_LENGTH..=GRID_SIZE {
+        for length in self.config.min_word_length..=self.config.max_word_length() {
             let wildcard_pattern = "_".repeat(length);
             self.real_intermediaries.insert(wildcard_pattern);
         }
@@ -142,107 +305,98 @@ impl CrosswordGraph {
         }
     }
 
+    /// Indexes the words by letter and position for [`CrosswordGraph::query`].
     pub fn build_graph(&mut self, verbose: bool) {
         if verbose {
-            println!("Building graph connections...");
-        }
-        
-        for word in &self.words {
-            let intermediaries = self.generate_intermediaries_for_word(word);
-            
-            // Only connect to real intermediaries
-            for intermediary in intermediaries {
-                if self.real_intermediaries.contains(&intermediary) {
-                    self.intermediary_to_words
-                        .entry(intermediary)
-                        .or_insert_with(HashSet::new)
-                        .insert(word.clone());
-                }
-            }
-        }
-        
-        // Handle pure underscore patterns (wildcards) - match all words of same length
-        for length in MIN_WORD_LENGTH..=GRID_SIZE {
-            let wildcard_pattern = "_".repeat(length);
-            if self.real_intermediaries.contains(&wildcard_pattern) {
-                // Connect to all words of this length
-                for word in &self.words {
-                    if word.len() == length {
-                        self.intermediary_to_words
-                            .entry(wildcard_pattern.clone())
-                            .or_insert_with(HashSet::new)
-                            .insert(word.clone());
-                    }
-                }
-            }
+            println!("Building pattern index...");
         }
-        
+
+        self.index = PatternIndex::new(&self.words);
+
         if verbose {
-            println!("Graph built with {} intermediary nodes", 
-                     self.intermediary_to_words.len());
+            println!("Pattern index built with {} letter bitsets", self.index.len());
         }
     }
 
+    /// Words matching `pattern`, where `_` matches any letter, in sorted
+    /// order.
+    pub fn query(&self, pattern: &str) -> impl Iterator<Item = &str> {
+        self.index.query(pattern)
+    }
+
     pub fn get_words_for_pattern(&self, pattern: &str) -> HashSet<String> {
-        self.intermediary_to_words.get(pattern).cloned().unwrap_or_default()
+        self.query(pattern).map(str::to_string).collect()
+    }
+
+    /// Number of words matching `pattern`, without copying them.
+    pub fn count_words_for_pattern(&self, pattern: &str) -> usize {
+        self.query(pattern).count()
     }
 
     pub fn words_match_pattern(&self, word: &str, pattern: &str) -> bool {
-        if word.len() != pattern.len() {
-            return false;
-        }
-        
-        word.chars().zip(pattern.chars()).all(|(w_char, p_char)| {
-            p_char == '_' || w_char == p_char
-        })
+        Word::new(word).matches(&Word::new(pattern))
     }
 
-    fn max_len_for_n_compound(&self, n: usize) -> usize {
+    fn max_len_for_n_compound(&self, n: usize, line_length: usize) -> usize {
         if n == 0 {
             return 0;
         }
-        let subtraction = (n - 1) * (MIN_WORD_LENGTH + 1);
-        if GRID_SIZE >= subtraction {
-            GRID_SIZE - subtraction
-        } else {
-            0
-        }
+        let subtraction = (n - 1) * (self.config.min_word_length + 1);
+        line_length.saturating_sub(subtraction)
     }
 
-    fn max_compounds_in_liner(&self) -> usize {
-        (GRID_SIZE + 1) / (MIN_WORD_LENGTH + 1)
+    fn max_compounds_in_liner(&self, line_length: usize) -> usize {
+        (line_length + 1) / (self.config.min_word_length + 1)
     }
 
+    fn is_line_length(&self, length: usize) -> bool {
+        self.config.line_lengths().contains(&length)
+    }
+
+    /// Generates the liners of every row and column length.
     pub fn generate_liners(&mut self, verbose: bool) {
         if verbose {
             println!("Generating liners...");
         }
         
-        let max_compounds = self.max_compounds_in_liner();
-        if verbose {
-            println!("Maximum compounds in liner: {}", max_compounds);
+        // Group intermediaries by length for efficient lookup
+        let mut intermediaries_by_length: HashMap<usize, Vec<String>> = HashMap::new();
+        for intermediary in &self.real_intermediaries {
+            intermediaries_by_length
+                .entry(intermediary.chars().count())
+                .or_default()
+                .push(intermediary.clone());
+        }
+
+        for line_length in self.config.line_lengths() {
+            self.generate_liners_of_length(line_length, &intermediaries_by_length, verbose);
         }
         
         if verbose {
+            println!("Generated {} liners", self.liners.len());
+        }
+    }
+
+    fn generate_liners_of_length(
+        &mut self,
+        line_length: usize,
+        intermediaries_by_length: &HashMap<usize, Vec<String>>,
+        verbose: bool,
+    ) {
+        let max_compounds = self.max_compounds_in_liner(line_length);
+        if verbose {
+            println!("Maximum compounds in a {}-cell liner: {}", line_length, max_compounds);
             for n in 1..=max_compounds {
-                let max_len = self.max_len_for_n_compound(n);
+                let max_len = self.max_len_for_n_compound(n, line_length);
                 println!("Max intermediary length for {}-compound: {}", n, max_len);
             }
         }
         
-        // Group intermediaries by length for efficient lookup
-        let mut intermediaries_by_length: HashMap<usize, Vec<String>> = HashMap::new();
+        // Type 1: Single intermediary padded to the line length with @
         for intermediary in &self.real_intermediaries {
-            intermediaries_by_length
-                .entry(intermediary.len())
-                .or_insert_with(Vec::new)
-                .push(intermediary.clone());
-        }
-        
-        // Type 1: Single intermediary padded to GRID_SIZE characters with @
-        for intermediary in &self.real_intermediaries {
-            if intermediary.len() <= GRID_SIZE {
-                let padding_needed = GRID_SIZE - intermediary.len();
+            let intermediary_len = intermediary.chars().count();
+            if intermediary_len <= line_length {
+                let padding_needed = line_length - intermediary_len;
                 
                 for start_pos in 0..=padding_needed {
                     let liner = format!("{}{}{}",
@@ -253,7 +407,7 @@ impl CrosswordGraph {
                     self.liners.insert(liner.clone());
                     self.liner_to_intermediaries
                         .entry(liner)
-                        .or_insert_with(HashSet::new)
+                        .or_default()
                         .insert(intermediary.clone());
                 }
             }
@@ -261,25 +415,22 @@ impl CrosswordGraph {
         
         // Type 2+: Multiple intermediaries (n-compounds where n >= 2)
         for n_compounds in 2..=max_compounds {
-            let max_len = self.max_len_for_n_compound(n_compounds);
+            let max_len = self.max_len_for_n_compound(n_compounds, line_length);
             
-            if max_len >= MIN_WORD_LENGTH {
-                self.generate_n_compound_liners(n_compounds, max_len, &intermediaries_by_length);
+            if max_len >= self.config.min_word_length {
+                self.generate_n_compound_liners(n_compounds, max_len, line_length, intermediaries_by_length);
             }
         }
-        
-        if verbose {
-            println!("Generated {} liners", self.liners.len());
-        }
     }
 
     fn generate_n_compound_liners(
         &mut self,
         n: usize,
         max_len: usize,
+        line_length: usize,
         intermediaries_by_length: &HashMap<usize, Vec<String>>,
     ) {
-        let valid_lengths: Vec<usize> = (MIN_WORD_LENGTH..=max_len)
+        let valid_lengths: Vec<usize> = (self.config.min_word_length..=max_len)
             .filter(|&length| intermediaries_by_length.contains_key(&length))
             .collect();
         
@@ -290,7 +441,7 @@ impl CrosswordGraph {
         for length_combo in (0..n).map(|_| valid_lengths.iter()).multi_cartesian_product() {
             let length_combo: Vec<usize> = length_combo.into_iter().cloned().collect();
             let content_length: usize = length_combo.iter().sum();
-            let separators_needed = GRID_SIZE - content_length;
+            let separators_needed = line_length.saturating_sub(content_length);
             
             if separators_needed >= n - 1 {
                 let intermediary_groups: Vec<&Vec<String>> = length_combo
@@ -306,14 +457,14 @@ impl CrosswordGraph {
                     
                     let unique_intermediaries: HashSet<&String> = intermediary_combo.iter().collect();
                     if unique_intermediaries.len() == n {
-                        self.create_liner_arrangements(&intermediary_combo, separators_needed);
+                        self.create_liner_arrangements(&intermediary_combo, separators_needed, line_length);
                     }
                 }
             }
         }
     }
 
-    fn create_liner_arrangements(&mut self, intermediaries: &[String], separators_needed: usize) {
+    fn create_liner_arrangements(&mut self, intermediaries: &[String], separators_needed: usize, line_length: usize) {
         let n = intermediaries.len();
         let min_separators = n - 1;
         let extra_separators = separators_needed - min_separators;
@@ -324,12 +475,12 @@ impl CrosswordGraph {
         
         if separators_needed == min_separators {
             let liner = intermediaries.join("@");
-            if liner.len() == GRID_SIZE {
+            if liner.chars().count() == line_length {
                 self.liners.insert(liner.clone());
                 for inter in intermediaries {
                     self.liner_to_intermediaries
                         .entry(liner.clone())
-                        .or_insert_with(HashSet::new)
+                        .or_default()
                         .insert(inter.clone());
                 }
             }
@@ -337,23 +488,23 @@ impl CrosswordGraph {
         
         if extra_separators > 0 {
             let liner = format!("{}{}", "@".repeat(extra_separators), intermediaries.join("@"));
-            if liner.len() == GRID_SIZE {
+            if liner.chars().count() == line_length {
                 self.liners.insert(liner.clone());
                 for inter in intermediaries {
                     self.liner_to_intermediaries
                         .entry(liner.clone())
-                        .or_insert_with(HashSet::new)
+                        .or_default()
                         .insert(inter.clone());
                 }
             }
             
             let liner = format!("{}{}", intermediaries.join("@"), "@".repeat(extra_separators));
-            if liner.len() == GRID_SIZE {
+            if liner.chars().count() == line_length {
                 self.liners.insert(liner.clone());
                 for inter in intermediaries {
                     self.liner_to_intermediaries
                         .entry(liner.clone())
-                        .or_insert_with(HashSet::new)
+                        .or_default()
                         .insert(inter.clone());
                 }
             }
@@ -366,12 +517,12 @@ impl CrosswordGraph {
                     intermediaries.join("@"),
                     "@".repeat(sep_end)
                 );
-                if liner.len() == GRID_SIZE {
+                if liner.chars().count() == line_length {
                     self.liners.insert(liner.clone());
                     for inter in intermediaries {
                         self.liner_to_intermediaries
                             .entry(liner.clone())
-                            .or_insert_with(HashSet::new)
+                            .or_default()
                             .insert(inter.clone());
                     }
                 }
@@ -384,7 +535,7 @@ impl CrosswordGraph {
     }
 
     pub fn parse_liner_components(&self, liner: &str) -> Vec<String> {
-        if liner.len() != GRID_SIZE {
+        if !self.is_line_length(liner.chars().count()) {
             return Vec::new();
         }
         
@@ -411,7 +562,7 @@ impl CrosswordGraph {
     }
 
     pub fn is_valid_liner(&self, liner: &str) -> bool {
-        if liner.len() != GRID_SIZE {
+        if !self.is_line_length(liner.chars().count()) {
             return false;
         }
         
@@ -427,16 +578,9 @@ impl CrosswordGraph {
     pub fn get_stats(&self) -> HashMap<String, f64> {
         let mut stats = HashMap::new();
         stats.insert("total_words".to_string(), self.words.len() as f64);
+        stats.insert("scored_words".to_string(), self.scores.len() as f64);
         stats.insert("real_intermediaries".to_string(), self.real_intermediaries.len() as f64);
-        stats.insert("graph_connections".to_string(), self.intermediary_to_words.len() as f64);
-        
-        let total_connections: usize = self.intermediary_to_words.values().map(|v| v.len()).sum();
-        let avg_words_per_intermediary = if self.intermediary_to_words.is_empty() {
-            0.0
-        } else {
-            total_connections as f64 / self.intermediary_to_words.len() as f64
-        };
-        stats.insert("avg_words_per_intermediary".to_string(), avg_words_per_intermediary);
+        stats.insert("index_bitsets".to_string(), self.index.len() as f64);
         
         stats.insert("total_liners".to_string(), self.liners.len() as f64);
         
@@ -452,6 +596,10 @@ impl CrosswordGraph {
     }
 
     // Additional methods for the maker
+    pub fn config(&self) -> &GraphConfig {
+        &self.config
+    }
+
     pub fn words(&self) -> &HashSet<String> {
         &self.words
     }
@@ -467,4 +615,14 @@ impl CrosswordGraph {
     pub fn words_that_can_be_checked_against(&self) -> &HashSet<String> {
         &self.words_that_can_be_checked_against
     }
+
+    /// Score of `word` in the dictionary, `DEFAULT_WORD_SCORE` if it has none.
+    pub fn word_score(&self, word: &str) -> u32 {
+        self.scores.get(word).copied().unwrap_or(DEFAULT_WORD_SCORE)
+    }
+
+    /// Whether any dictionary loaded gave its words scores.
+    pub fn has_scores(&self) -> bool {
+        !self.scores.is_empty()
+    }
 }
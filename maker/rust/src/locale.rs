@@ -0,0 +1,100 @@
+use std::fmt;
+use std::str::FromStr;
+
+const COMBINING_DOT_ABOVE: char = '\u{0307}';
+
+/// Case conversion rules for a puzzle language.
+///
+/// Mirrors what the site does with `toLocaleUpperCase(lang)`, so words
+/// survive the trip between the lowercase dictionary and uppercase puzzle
+/// files. Turkish keeps dotted `i`/`İ` and dotless `ı`/`I` apart; English
+/// uses the default Unicode mappings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Locale {
+    #[default]
+    Turkish,
+    English,
+}
+
+impl Locale {
+    /// Looks up a locale by language code (`tr`, `en`, `tr-TR`, `en_US`, ...).
+    pub fn from_code(code: &str) -> Option<Self> {
+        let language = code
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match language.as_str() {
+            "tr" => Some(Locale::Turkish),
+            "en" => Some(Locale::English),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::Turkish => "tr",
+            Locale::English => "en",
+        }
+    }
+
+    pub fn to_lowercase(&self, text: &str) -> String {
+        match self {
+            Locale::Turkish => {
+                let mut lowered = String::with_capacity(text.len());
+                let mut chars = text.chars().peekable();
+                while let Some(ch) = chars.next() {
+                    match ch {
+                        'İ' => lowered.push('i'),
+                        'I' => {
+                            // "I" followed by a combining dot is a decomposed "İ"
+                            if chars.peek() == Some(&COMBINING_DOT_ABOVE) {
+                                chars.next();
+                                lowered.push('i');
+                            } else {
+                                lowered.push('ı');
+                            }
+                        }
+                        _ => lowered.extend(ch.to_lowercase()),
+                    }
+                }
+                lowered
+            }
+            Locale::English => text.to_lowercase(),
+        }
+    }
+
+    pub fn to_uppercase(&self, text: &str) -> String {
+        match self {
+            Locale::Turkish => text
+                .chars()
+                .flat_map(|ch| match ch {
+                    'i' => vec!['İ'],
+                    'ı' => vec!['I'],
+                    _ => ch.to_uppercase().collect(),
+                })
+                .collect(),
+            Locale::English => text.to_uppercase(),
+        }
+    }
+
+    /// Normalizes a raw dictionary or puzzle entry into a lowercase word
+    /// without surrounding or inner whitespace.
+    pub fn normalize_word(&self, text: &str) -> String {
+        self.to_lowercase(text.trim()).replace(' ', "")
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        Self::from_code(code).ok_or_else(|| format!("unsupported language code '{}'", code))
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
@@ -1,16 +1,32 @@
 use clap::Parser;
-use crossword_grapher::{CrosswordGraph, GRID_SIZE, MIN_WORD_LENGTH};
-use rand::prelude::*;
+use crossword_grapher::solver::solve_portfolio;
+use crossword_grapher::{encryption, export};
+use crossword_grapher::{
+    Archive, CancellationToken, CrosswordGraph, CrosswordGrid, CrosswordSolver, Direction, GraphConfig,
+    LanguageProfile, SeedWord, SolveOutcome, Symmetry, Template, Theme, WordLayer, DEFAULT_GRID_SIZE, DEFAULT_MIN_WORD_LENGTH,
+};
 use std::collections::HashSet;
 use std::fs;
-use std::time::Instant;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// Exit codes for the ways a run can end without a solution
+const EXIT_EXHAUSTED: u8 = 2;
+const EXIT_BUDGET_EXCEEDED: u8 = 3;
+const EXIT_CANCELLED: u8 = 4;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Input file containing words
-    #[arg(short, long, default_value = "../turkish_words.txt")]
-    input: String,
+    /// Input file containing words (default: the language's dictionary)
+    #[arg(short, long)]
+    input: Option<String>,
+    
+    /// Word list layered over the dictionary, in order: add:FILE or
+    /// remove:FILE, optionally labelled as add:LABEL=FILE. Repeatable
+    #[arg(long = "layer", value_name = "KIND:FILE")]
+    layers: Vec<WordLayer>,
     
     /// Enable verbose output
     #[arg(short, long)]
@@ -24,787 +40,350 @@ struct Args {
     #[arg(short, long, default_value = "100")]
     max_attempts: usize,
     
-    /// Random seed for reproducible results
+    /// Stop after this many seconds and print the best grid found so far
+    #[arg(short, long)]
+    timeout: Option<f64>,
+    
+    /// Random seed for reproducible results; with --jobs, the base seed
+    /// the jobs' seeds are derived from
     #[arg(short, long)]
     seed: Option<u64>,
-}
 
-#[derive(Debug, Clone)]
-struct WordPlacement {
-    word: String,
-    row: usize,
-    col: usize,
-    direction: Direction,
+    /// Run this many solvers with different seeds in parallel over one graph
+    #[arg(short, long, default_value = "1")]
+    jobs: usize,
+
+    /// Grid size (number of rows and columns)
+    #[arg(long, default_value_t = DEFAULT_GRID_SIZE)]
+    size: usize,
+
+    /// Row length, for rectangular grids (default: --size)
+    #[arg(long)]
+    width: Option<usize>,
+
+    /// Number of rows, for rectangular grids (default: --size)
+    #[arg(long)]
+    height: Option<usize>,
+
+    /// Minimum word length
+    #[arg(long, default_value_t = DEFAULT_MIN_WORD_LENGTH)]
+    min_len: usize,
+
+    /// Language code (tr, en) or path to a language profile file
+    #[arg(long, default_value = "tr")]
+    lang: String,
+
+    /// Template file with blocks (# or -), empty cells (.) and fixed letters;
+    /// sets the grid's width and height
+    #[arg(long)]
+    template: Option<String>,
+
+    /// Skip intermediaries and liners: every run between blocks, whole lines
+    /// included, takes one word. Much faster to build for long words. Slot
+    /// candidates are then pruned against their crossings on every line; in
+    /// open grids otherwise, only on lines that hold a block or are no longer
+    /// than --min-len
+    #[arg(long)]
+    no_intermediaries: bool,
+
+    /// Graph cache file, reused when it matches the dictionary and config
+    #[arg(long)]
+    cache: Option<String>,
+
+    /// Fill the most constrained entry first, least constraining words first,
+    /// instead of trying placements in random order
+    #[arg(long)]
+    heuristic: bool,
+
+    /// Reject grids whose average word score is below this (see `word;score`
+    /// dictionary lines)
+    #[arg(long, value_name = "SCORE")]
+    min_score: Option<f64>,
+
+    /// Directory of published puzzles (e.g. ../../tr) whose recent answers
+    /// the grid must not reuse. Repeatable
+    #[arg(long = "archive", value_name = "DIR")]
+    archives: Vec<String>,
+
+    /// With --archive, avoid answers published less than this many days
+    /// before or after --date
+    #[arg(long, value_name = "DAYS", default_value = "365")]
+    cooldown: usize,
+
+    /// Word the grid has to use: WORD, or WORD@row,col,h|v to place it there
+    /// before filling. Repeatable
+    #[arg(long = "seed-word", value_name = "WORD[@ROW,COL,DIR]")]
+    seed_words: Vec<SeedWord>,
+
+    /// File of theme words, one per line, at least --theme-min of which the
+    /// grid has to use
+    #[arg(long, value_name = "FILE")]
+    theme_list: Option<String>,
+
+    /// With --theme-list, how many of its words the grid has to use
+    #[arg(long, value_name = "K", default_value = "1", requires = "theme_list")]
+    theme_min: usize,
+
+    /// Block symmetry to keep: none, rotational, horizontal, vertical or diagonal
+    #[arg(long, default_value = "none")]
+    symmetry: Symmetry,
+
+    /// Write a solved grid as a site puzzle file, DIR/DATE.txt (e.g. ../../tr);
+    /// with --solutions, one file per solution on consecutive dates
+    #[arg(long, value_name = "DIR", requires = "author")]
+    export: Option<String>,
+
+    /// Author name for the exported puzzle
+    #[arg(long)]
+    author: Option<String>,
+
+    /// Publish date of the exported puzzle, YYYY-MM-DD (default: today)
+    #[arg(long)]
+    date: Option<String>,
+
+    /// Keep searching until this many distinct solutions are found, saving
+    /// each to --solutions-dir as it is found
+    #[arg(long, default_value = "1")]
+    solutions: usize,
+
+    /// Count solutions using the same set of words as one
+    #[arg(long)]
+    distinct_words: bool,
+
+    /// Directory solutions are saved to when --solutions is above 1
+    #[arg(long, default_value = "solutions")]
+    solutions_dir: String,
+
+    /// Encrypt the exported puzzle: v1 ([ENCRYPTED], what the site reads) or
+    /// v2 ([ENCRYPTED:v2], keyed with the date)
+    #[arg(long, value_name = "FORMAT", requires = "export", num_args = 0..=1, default_missing_value = "v1")]
+    encrypt: Option<encryption::Format>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum Direction {
-    Horizontal,
-    Vertical,
-}
-
-impl WordPlacement {
-    fn get_positions(&self) -> Vec<(usize, usize)> {
-        let mut positions = Vec::new();
-        for (i, _) in self.word.chars().enumerate() {
-            match self.direction {
-                Direction::Horizontal => positions.push((self.row, self.col + i)),
-                Direction::Vertical => positions.push((self.row + i, self.col)),
-            }
-        }
-        positions
+fn print_placed_words(grid: &CrosswordGrid) {
+    println!("Placed words:");
+    for (i, word_placement) in grid.word_placements().iter().enumerate() {
+        println!("{}. {} at ({}, {}) {:?}", i + 1, word_placement.word, word_placement.row, word_placement.col, word_placement.direction);
     }
 }
 
-#[derive(Debug, Clone)]
-struct CrosswordGrid {
-    grid: Vec<Vec<char>>,
-    word_placements: Vec<WordPlacement>,
-    words_on_grid: HashSet<String>,
-    filled_cells: usize,
-    graph: CrosswordGraph,
-}
-
-impl CrosswordGrid {
-    fn new(graph: CrosswordGraph) -> Self {
-        Self {
-            grid: vec![vec!['.'; GRID_SIZE]; GRID_SIZE],
-            word_placements: Vec::new(),
-            words_on_grid: HashSet::new(),
-            filled_cells: 0,
-            graph,
-        }
-    }
-
-    fn can_place_word(&self, word: &str, row: usize, col: usize, direction: Direction) -> bool {
-        // Early exit if word is already placed
-        if self.words_on_grid.contains(word) {
-            return false;
-        }
-
-        match direction {
-            Direction::Horizontal => {
-                if col + word.len() > GRID_SIZE {
-                    return false;
-                }
-                for (i, ch) in word.chars().enumerate() {
-                    let cell = self.grid[row][col + i];
-                    if cell != '.' && cell != ch {
-                        return false;
-                    }
-                }
-            }
-            Direction::Vertical => {
-                if row + word.len() > GRID_SIZE {
-                    return false;
-                }
-                for (i, ch) in word.chars().enumerate() {
-                    let cell = self.grid[row + i][col];
-                    if cell != '.' && cell != ch {
-                        return false;
-                    }
-                }
-            }
-        }
-        true
-    }
-
-    fn place_word(&mut self, word: &str, row: usize, col: usize, direction: Direction, verbose: bool) -> bool {
-        if !self.can_place_word(word, row, col, direction) {
-            if verbose {
-                println!("      ❌ Cannot place '{}' - conflicts with existing letters", word);
-            }
-            return false;
-        }
-
-        // Create backup
-        let old_grid = self.grid.clone();
-        let old_placements = self.word_placements.clone();
-        let old_words_on_grid = self.words_on_grid.clone();
-        let old_filled_cells = self.filled_cells;
-
-        // Place the word
-        let placement = WordPlacement {
-            word: word.to_string(),
-            row,
-            col,
-            direction,
-        };
-
-        let positions = placement.get_positions();
-        for (i, (r, c)) in positions.iter().enumerate() {
-            let ch = word.chars().nth(i).unwrap();
-            if self.grid[*r][*c] == '.' {
-                self.filled_cells += 1;
-            }
-            self.grid[*r][*c] = ch;
-        }
-
-        self.word_placements.push(placement);
-        self.words_on_grid.insert(word.to_string());
-
-        // Check if we're close to completion and save grid
-        let empty_cells = (GRID_SIZE * GRID_SIZE) - self.filled_cells;
-        if empty_cells <= (GRID_SIZE / 2) {
-            if self.is_solvable_grid() {
-                self.save_grid_to_file("solvables/");
-            } else if empty_cells <= (GRID_SIZE / 2).saturating_sub(1) {
-                self.save_grid_to_file("close_calls/");
-            }
-        }
-
-        // Final validation of the grid state
-        if self.validate_grid_state(verbose) {
-            if verbose {
-                println!("      ✅ '{}' placed successfully and grid state validated", word);
-            }
-            true
-        } else {
-            if verbose {
-                println!("      ❌ '{}' placed but failed final grid state validation", word);
-            }
-            // Restore backup
-            self.grid = old_grid;
-            self.word_placements = old_placements;
-            self.words_on_grid = old_words_on_grid;
-            self.filled_cells = old_filled_cells;
-            false
-        }
-    }
-
-    fn get_row_state(&self, row: usize) -> String {
-        self.grid[row].iter().collect()
-    }
-
-    fn get_col_state(&self, col: usize) -> String {
-        (0..GRID_SIZE).map(|row| self.grid[row][col]).collect()
-    }
-
-    fn validate_grid_state(&self, verbose: bool) -> bool {
-        // Check all rows
-        for row in 0..GRID_SIZE {
-            let row_state = self.get_row_state(row);
-            if !self.validate_row_column(&row_state) {
-                if verbose {
-                    println!("      🔍 Row {} validation failed: '{}'", row, row_state);
-                }
-                return false;
-            }
-        }
-
-        // Check all columns
-        for col in 0..GRID_SIZE {
-            let col_state = self.get_col_state(col);
-            if !self.validate_row_column(&col_state) {
-                if verbose {
-                    println!("      🔍 Column {} validation failed: '{}'", col, col_state);
-                }
-                return false;
-            }
-        }
-
-        // Additional check: every filled cell must be able to form wordful liners
-        self.validate_cell_wordful_constraints(verbose)
-    }
-
-    fn validate_cell_wordful_constraints(&self, verbose: bool) -> bool {
-        for row in 0..GRID_SIZE {
-            for col in 0..GRID_SIZE {
-                if self.grid[row][col] != '.' {
-                    let row_state = self.get_row_state(row);
-                    if !self.can_form_wordful_liner(&row_state, verbose) {
-                        if verbose {
-                            println!("      🔍 Cell ({},{}) cannot form wordful horizontal liner: '{}'", row, col, row_state);
-                        }
-                        return false;
-                    }
-
-                    let col_state = self.get_col_state(col);
-                    if !self.can_form_wordful_liner(&col_state, verbose) {
-                        if verbose {
-                            println!("      🔍 Cell ({},{}) cannot form wordful vertical liner: '{}'", row, col, col_state);
-                        }
-                        return false;
-                    }
-                }
-            }
-        }
-        true
-    }
-
-    fn can_form_wordful_liner(&self, current_state: &str, _verbose: bool) -> bool {
-        let empty_positions: Vec<usize> = current_state
-            .chars()
-            .enumerate()
-            .filter(|(_, c)| *c == '.')
-            .map(|(i, _)| i)
-            .collect();
-
-        if empty_positions.is_empty() {
-            // No empty cells - check if current state is valid
-            return self.graph.words().contains(current_state) || self.graph.liners().contains(current_state);
-        }
-
-        // Special case: if the entire state is empty, it's always valid
-        if empty_positions.len() == GRID_SIZE {
-            return true;
-        }
-
-        // Try simple patterns
-        let mut pattern1 = current_state.chars().collect::<Vec<_>>();
-        for &pos in &empty_positions {
-            pattern1[pos] = '_';
-        }
-        let pattern1_str: String = pattern1.iter().collect();
-        if self.is_wordful_liner(&pattern1_str) {
-            return true;
-        }
-
-        let mut pattern2 = current_state.chars().collect::<Vec<_>>();
-        for &pos in &empty_positions {
-            pattern2[pos] = '@';
-        }
-        let pattern2_str: String = pattern2.iter().collect();
-        if self.is_wordful_liner(&pattern2_str) {
-            return true;
-        }
-
-        // Try more combinations if we have few empty positions
-        if empty_positions.len() <= 2 {
-            let replacements = vec!['_', '@'];
-            for combo in (0..empty_positions.len()).map(|_| replacements.iter()).multi_cartesian_product() {
-                let mut candidate = current_state.chars().collect::<Vec<_>>();
-                for (pos, replacement) in empty_positions.iter().zip(combo) {
-                    candidate[*pos] = *replacement;
-                }
-                let candidate_str: String = candidate.iter().collect();
-                if self.is_wordful_liner(&candidate_str) {
-                    return true;
-                }
-            }
-        }
-
-        false
-    }
-
-    fn is_wordful_liner(&self, liner_pattern: &str) -> bool {
-        if !self.graph.liners().contains(liner_pattern) {
-            return false;
-        }
-
-        let intermediaries = self.graph.get_intermediaries_for_liner(liner_pattern);
-        for intermediary in intermediaries {
-            let words = self.graph.get_words_for_pattern(&intermediary);
-            if !words.is_empty() {
-                return true;
-            }
-        }
-        false
-    }
-
-    fn validate_row_column(&self, current_state: &str) -> bool {
-        let empty_positions: Vec<usize> = current_state
-            .chars()
-            .enumerate()
-            .filter(|(_, c)| *c == '.')
-            .map(|(i, _)| i)
-            .collect();
-
-        if empty_positions.is_empty() {
-            return self.graph.liners().contains(current_state) || self.graph.words().contains(current_state);
-        }
-
-        if empty_positions.len() == GRID_SIZE {
-            return true;
-        }
-
-        // Try common patterns
-        let test_patterns = vec![
-            current_state.replace('.', "_"),
-            current_state.replace('.', "@"),
-        ];
-
-        for pattern in test_patterns {
-            if self.is_achievable_liner(&pattern) {
-                return true;
-            }
-        }
-
-        // Try more combinations for small number of empty positions
-        if empty_positions.len() <= 3 {
-            let replacements = vec!['_', '@'];
-            for combo in (0..empty_positions.len()).map(|_| replacements.iter()).multi_cartesian_product() {
-                let mut candidate = current_state.chars().collect::<Vec<_>>();
-                for (pos, replacement) in empty_positions.iter().zip(combo) {
-                    candidate[*pos] = *replacement;
-                }
-                let candidate_str: String = candidate.iter().collect();
-                if self.is_achievable_liner(&candidate_str) {
-                    return true;
-                }
-            }
-        }
-
-        false
-    }
-
-    fn is_achievable_liner(&self, liner_pattern: &str) -> bool {
-        self.is_wordful_liner(liner_pattern)
-    }
-
-    fn get_possible_placements(&self, rng: &mut StdRng) -> Vec<(String, usize, usize, Direction)> {
-        let mut placements = Vec::new();
-
-        // Check each row for possible horizontal placements
-        for row in 0..GRID_SIZE {
-            let row_state = self.get_row_state(row);
-            if row_state.contains('.') {
-                let row_placements = self.get_placements_for_line(&row_state, row, Direction::Horizontal);
-                placements.extend(row_placements);
-            }
-        }
-
-        // Check each column for possible vertical placements
-        for col in 0..GRID_SIZE {
-            let col_state = self.get_col_state(col);
-            if col_state.contains('.') {
-                let col_placements = self.get_placements_for_line(&col_state, col, Direction::Vertical);
-                placements.extend(col_placements);
-            }
-        }
-
-        // Filter out words already placed
-        let mut valid_placements: Vec<_> = placements
-            .into_iter()
-            .filter(|(word, _, _, _)| !self.words_on_grid.contains(word))
-            .collect();
-
-        // Prioritize by word length (GRID_SIZE first) and randomize within each group
-        let mut grid_size_placements: Vec<_> = valid_placements
-            .iter()
-            .filter(|(word, _, _, _)| word.len() == GRID_SIZE)
-            .cloned()
-            .collect();
-        let mut shorter_placements: Vec<_> = valid_placements
-            .iter()
-            .filter(|(word, _, _, _)| word.len() < GRID_SIZE)
-            .cloned()
-            .collect();
-
-        grid_size_placements.shuffle(rng);
-        shorter_placements.shuffle(rng);
-
-        if !grid_size_placements.is_empty() {
-            grid_size_placements
-        } else {
-            shorter_placements
-        }
-    }
-
-    fn get_placements_for_line(&self, line_state: &str, line_index: usize, direction: Direction) -> Vec<(String, usize, usize, Direction)> {
-        let mut placements = Vec::new();
-        let empty_positions: Vec<usize> = line_state
-            .chars()
-            .enumerate()
-            .filter(|(_, c)| *c == '.')
-            .map(|(i, _)| i)
-            .collect();
-
-        if empty_positions.is_empty() {
-            return placements;
-        }
-
-        let possible_liners = self.generate_liner_patterns(line_state);
-
-        for liner_pattern in possible_liners {
-            if self.graph.liners().contains(&liner_pattern) {
-                let intermediaries = self.graph.get_intermediaries_for_liner(&liner_pattern);
-
-                for intermediary in intermediaries {
-                    let words = self.graph.get_words_for_pattern(&intermediary);
-
-                    for word in words {
-                        let word_placements = self.find_word_positions_in_liner(&word, &liner_pattern, line_state);
-
-                        for start_pos in word_placements {
-                            match direction {
-                                Direction::Horizontal => {
-                                    placements.push((word.clone(), line_index, start_pos, Direction::Horizontal));
-                                }
-                                Direction::Vertical => {
-                                    placements.push((word.clone(), start_pos, line_index, Direction::Vertical));
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        placements
-    }
-
-    fn generate_liner_patterns(&self, line_state: &str) -> Vec<String> {
-        let empty_positions: Vec<usize> = line_state
-            .chars()
-            .enumerate()
-            .filter(|(_, c)| *c == '.')
-            .map(|(i, _)| i)
-            .collect();
-
-        if empty_positions.is_empty() {
-            return vec![line_state.to_string()];
-        }
-
-        let mut patterns = Vec::new();
-
-        if empty_positions.len() <= 3 {
-            let replacements = vec!['_', '@'];
-            for combo in (0..empty_positions.len()).map(|_| replacements.iter()).multi_cartesian_product() {
-                let mut pattern = line_state.chars().collect::<Vec<_>>();
-                for (pos, replacement) in empty_positions.iter().zip(combo) {
-                    pattern[*pos] = *replacement;
-                }
-                patterns.push(pattern.iter().collect());
-            }
-        } else {
-            // For larger number of empty positions, try only common patterns
-            patterns.push(line_state.replace('.', "_"));
-            patterns.push(line_state.replace('.', "@"));
-
-            if empty_positions.len() >= 2 {
-                let mid = empty_positions.len() / 2;
-                let mut pattern3 = line_state.chars().collect::<Vec<_>>();
-                for (i, &pos) in empty_positions.iter().enumerate() {
-                    pattern3[pos] = if i < mid { '_' } else { '@' };
-                }
-                patterns.push(pattern3.iter().collect());
-
-                let mut pattern4 = line_state.chars().collect::<Vec<_>>();
-                for (i, &pos) in empty_positions.iter().enumerate() {
-                    pattern4[pos] = if i < mid { '@' } else { '_' };
-                }
-                patterns.push(pattern4.iter().collect());
-            }
-        }
-
-        // Filter to only return patterns that exist in our liner set
-        patterns
-            .into_iter()
-            .filter(|pattern| self.graph.liners().contains(pattern))
-            .collect()
+fn print_fill_score(grid: &CrosswordGrid) {
+    if !grid.graph().has_scores() {
+        return;
     }
-
-    fn find_word_positions_in_liner(&self, word: &str, liner_pattern: &str, line_state: &str) -> Vec<usize> {
-        let mut valid_positions = Vec::new();
-
-        for start_pos in 0..=(GRID_SIZE - word.len()) {
-            let mut can_place = true;
-
-            for (i, ch) in word.chars().enumerate() {
-                let liner_pos = start_pos + i;
-                let liner_char = liner_pattern.chars().nth(liner_pos).unwrap();
-                let line_char = line_state.chars().nth(liner_pos).unwrap();
-
-                if liner_char == '@' {
-                    can_place = false;
-                    break;
-                } else if liner_char != '_' && liner_char != ch {
-                    can_place = false;
-                    break;
-                } else if line_char != '.' && line_char != ch {
-                    can_place = false;
-                    break;
-                }
-            }
-
-            if can_place {
-                valid_positions.push(start_pos);
-            }
-        }
-
-        valid_positions
-    }
-
-    fn is_complete(&self) -> bool {
-        self.filled_cells == GRID_SIZE * GRID_SIZE
+    if let Some(score) = grid.fill_score() {
+        println!("Word score: average {:.1}, minimum {}", score.average, score.minimum);
     }
+}
 
-    fn print_grid(&self) {
-        println!("📋 Current Grid:");
-        print!("   ");
-        for i in 0..GRID_SIZE {
-            print!("{} ", i);
-        }
-        println!();
-
-        for (i, row) in self.grid.iter().enumerate() {
-            print!(" {} ", i);
-            for &cell in row {
-                print!("{} ", if cell == '.' { '·' } else { cell });
-            }
-            println!();
-        }
-
-        let total_cells = GRID_SIZE * GRID_SIZE;
-        let fill_percentage = (self.filled_cells as f64 / total_cells as f64) * 100.0;
-        println!("📈 Fill progress: {}/{} cells ({:.1}%)", self.filled_cells, total_cells, fill_percentage);
-        println!();
+fn print_theme(grid: &CrosswordGrid, theme: &Theme) {
+    if theme.is_empty() {
+        return;
     }
+    let landed = theme.landed(grid);
+    println!("Theme entries: {} landed ({})", landed.len(), landed.join(", "));
+}
 
-    fn is_solvable_grid(&self) -> bool {
-        // Check if all rows and columns can still lead to valid liners
-        for row in 0..GRID_SIZE {
-            if !self.is_solvable_row(row) {
-                return false;
-            }
-        }
-        for col in 0..GRID_SIZE {
-            if !self.is_solvable_column(col) {
-                return false;
-            }
-        }
-        true
-    }
+/// What the grid has to respect besides the dictionary.
+struct Constraints {
+    template: Option<Template>,
+    // Seed words with a position, normalized
+    placed: Vec<(String, usize, usize, Direction)>,
+    theme: Theme,
+    avoided: HashSet<String>,
+}
 
-    fn is_solvable_row(&self, row: usize) -> bool {
-        let row_state = self.get_row_state(row);
-        self.is_solvable_line_state(&row_state)
+fn build_solver(
+    args: &Args,
+    graph: &Arc<CrosswordGraph>,
+    constraints: &Constraints,
+    seed: Option<u64>,
+    start_time: Instant,
+) -> Result<CrosswordSolver, String> {
+    let mut solver = CrosswordSolver::new(Arc::clone(graph), seed, args.max_attempts)
+        .with_heuristic(args.heuristic)
+        .with_min_score(args.min_score)
+        .with_avoided_words(constraints.avoided.clone())
+        .with_theme(constraints.theme.clone())
+        .with_progress_files(true);
+    if let Some(template) = &constraints.template {
+        solver = solver.with_template(template)?;
     }
-
-    fn is_solvable_column(&self, col: usize) -> bool {
-        let col_state = self.get_col_state(col);
-        self.is_solvable_line_state(&col_state)
+    solver = solver.with_symmetry(args.symmetry)?;
+    for (word, row, col, direction) in &constraints.placed {
+        solver = solver.with_placed_word(word, *row, *col, *direction)?;
     }
-
-    fn is_solvable_line_state(&self, line_state: &str) -> bool {
-        let words: Vec<&str> = line_state.split('.').collect();
-        words.iter().all(|&word| {
-            word.is_empty() || (self.graph.words().contains(word) && word.len() >= MIN_WORD_LENGTH)
-        })
-    }
-
-    fn save_grid_to_file(&self, dirname: &str) {
-        let signature = self.get_grid_state_signature();
-        let filename = format!("{}/grid_{}x{}_{}_empty_{}.txt", dirname, GRID_SIZE, GRID_SIZE, signature.matches('-').count(), signature);
-        
-        if let Err(e) = fs::create_dir_all(dirname) {
-            eprintln!("Error creating directory: {}", e);
-            return;
-        }
-
-        let mut content = String::new();
-        content.push_str(&format!("Grid saved\n"));
-        content.push_str(&format!("Filled cells: {}/{}\n", self.filled_cells, GRID_SIZE * GRID_SIZE));
-        content.push_str(&format!("Words placed: {}\n\n", self.word_placements.len()));
-
-        content.push_str("Grid:\n");
-        content.push_str("   ");
-        for i in 0..GRID_SIZE {
-            content.push_str(&format!("{} ", i));
-        }
-        content.push('\n');
-
-        for (i, row) in self.grid.iter().enumerate() {
-            content.push_str(&format!(" {} ", i));
-            for &cell in row {
-                content.push_str(&format!("{} ", if cell == '.' { '·' } else { cell }));
-            }
-            content.push('\n');
-        }
-
-        content.push_str("\nPlaced words:\n");
-        for (i, word) in self.word_placements.iter().enumerate() {
-            content.push_str(&format!("{:2}. '{}' at ({},{}) {:?}\n", i + 1, word.word, word.row, word.col, word.direction));
-        }
-
-        let unique_words: Vec<String> = self.words_on_grid.iter().cloned().collect();
-        content.push_str(&format!("\nUnique words placed ({}):\n", unique_words.len()));
-        for (i, word) in unique_words.iter().enumerate() {
-            content.push_str(&format!("{:2}. {}\n", i + 1, word));
-        }
-
-        if let Err(e) = fs::write(&filename, content) {
-            eprintln!("Error saving grid to file: {}", e);
-        }
-    }
-
-    fn get_grid_state_signature(&self) -> String {
-        self.grid
-            .iter()
-            .map(|row| row.iter().collect::<String>())
-            .collect::<Vec<_>>()
-            .join("")
-            .replace('.', "-")
+    if let Some(timeout) = args.timeout {
+        solver = solver.with_deadline(start_time + Duration::from_secs_f64(timeout));
     }
+    Ok(solver)
 }
 
-struct CrosswordSolver {
-    graph: CrosswordGraph,
-    rng: StdRng,
-    attempts: usize,
-    max_attempts: usize,
-    visited_states: HashSet<String>,
+/// Prints, saves and exports the solutions of a `--solutions` run as they
+/// are found, the n-th one for the date n-1 days after `--date`.
+struct SolutionSink {
+    found: usize,
+    solutions_dir: String,
+    export_to: Option<(String, String)>,
+    encrypt: Option<encryption::Format>,
+    first_date: String,
+    theme: Theme,
 }
 
-impl CrosswordSolver {
-    fn new(graph: CrosswordGraph, seed: Option<u64>, max_attempts: usize) -> Self {
-        let rng = if let Some(seed) = seed {
-            StdRng::seed_from_u64(seed)
-        } else {
-            StdRng::from_entropy()
-        };
-
+impl SolutionSink {
+    fn new(args: &Args, first_date: &str, theme: &Theme) -> Self {
         Self {
-            graph,
-            rng,
-            attempts: 0,
-            max_attempts,
-            visited_states: HashSet::new(),
+            found: 0,
+            solutions_dir: args.solutions_dir.clone(),
+            export_to: args.export.clone().zip(args.author.clone()),
+            encrypt: args.encrypt,
+            first_date: first_date.to_string(),
+            theme: theme.clone(),
         }
     }
 
-    fn solve(&mut self, verbose: bool) -> Option<CrosswordGrid> {
-        let mut grid = CrosswordGrid::new(self.graph.clone());
-        self.attempts = 0;
-        self.visited_states.clear();
-
-        if verbose {
-            println!("🚀 Starting DFS crossword generation...");
-            println!("📊 Graph statistics:");
-            println!("   - Total words: {}", self.graph.words().len());
-            println!("   - {}-letter words (priority): {}", GRID_SIZE, self.graph.words().iter().filter(|w| w.len() == GRID_SIZE).count());
-            println!("   - Shorter words: {}", self.graph.words().iter().filter(|w| w.len() < GRID_SIZE).count());
-            println!("   - Total liners: {}", self.graph.liners().len());
-            println!("   - Max attempts: {}", self.max_attempts);
-            println!("📏 Grid size: {}x{}", GRID_SIZE, GRID_SIZE);
-            println!("🎯 Strategy: Prioritize {}-letter words, use padded liners for shorter words", GRID_SIZE);
-        }
-
-        let result = self.dfs_solve(&mut grid, Vec::new(), verbose);
-
-        match result {
-            Some(solution) => {
-                if verbose {
-                    println!("\n🎉 SOLUTION FOUND after {} attempts!", self.attempts);
-                }
-                Some(solution)
-            }
-            None => {
-                if verbose {
-                    println!("\n😞 No solution found after {} attempts.", self.attempts);
-                }
-                None
+    fn add(&mut self, grid: &CrosswordGrid, seed: Option<u64>) {
+        self.found += 1;
+        match seed {
+            Some(seed) => println!("\n=== SOLUTION {} (seed {}) ===", self.found, seed),
+            None => println!("\n=== SOLUTION {} ===", self.found),
+        }
+        grid.print_grid();
+        print_fill_score(grid);
+        print_theme(grid, &self.theme);
+        grid.save_grid_to_file(&self.solutions_dir);
+        if let Some((dir, author)) = &self.export_to {
+            let exported = export::add_days(&self.first_date, self.found - 1)
+                .map_err(|e| e.into())
+                .and_then(|date| export::write_puzzle(grid, author, dir, &date, self.encrypt));
+            match exported {
+                Ok(path) => println!("Exported puzzle to {}", path.display()),
+                Err(e) => eprintln!("Error exporting solution {}: {}", self.found, e),
             }
         }
     }
+}
 
-    fn dfs_solve(&mut self, grid: &mut CrosswordGrid, placement_history: Vec<(String, usize, usize, Direction)>, verbose: bool) -> Option<CrosswordGrid> {
-        self.attempts += 1;
-
-        if self.attempts > self.max_attempts {
-            println!("Max attempts ({}) reached after {} attempts. Stopping.", self.max_attempts, self.attempts);
-            panic!("Max attempts reached - terminating program");
-        }
-
-        // Create grid state signature
-        let grid_signature = grid.get_grid_state_signature();
-        if self.visited_states.contains(&grid_signature) {
-            return None;
-        }
-
-        // Show progress
-        if self.attempts <= 3 || self.attempts % 25 == 0 {
-            if verbose {
-                println!("\n🔄 Attempt {} - {} words placed", self.attempts, grid.word_placements.len());
-                grid.print_grid();
-            }
-        }
-
-        // Check if grid is complete
-        if grid.is_complete() {
-            if verbose {
-                println!("\n🎉 SOLUTION FOUND! Grid is complete after {} attempts!", self.attempts);
-                println!("Final grid:");
-                grid.print_grid();
-            }
-            return Some(grid.clone());
-        }
-
-        // Get possible word placements
-        let placements = grid.get_possible_placements(&mut self.rng);
-
-        if placements.is_empty() {
-            if verbose {
-                println!("❌ No valid placements found at attempt {}. Backtracking...", self.attempts);
-            }
-            return None;
-        }
-
-        // Filter out placements we've already tried in this branch
-        let new_placements: Vec<_> = placements
-            .into_iter()
-            .filter(|placement| !placement_history.contains(placement))
-            .collect();
-
-        if new_placements.is_empty() {
-            if verbose {
-                println!("❌ No new placements available at attempt {}. Backtracking...", self.attempts);
-            }
-            return None;
-        }
-
-        if self.attempts <= 3 && verbose {
-            println!("Trying {} new placements at attempt {}...", new_placements.len(), self.attempts);
-        }
+/// Streams up to `--solutions` distinct grids to `--solutions-dir`, and to
+/// `--export` with one date per solution, as the solver finds them.
+fn enumerate_solutions(
+    args: &Args,
+    solver: &mut CrosswordSolver,
+    theme: &Theme,
+    first_date: &str,
+    start_time: Instant,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    fs::create_dir_all(&args.solutions_dir)?;
+    let mut sink = SolutionSink::new(args, first_date, theme);
+    let enumeration = solver.enumerate(args.solutions, args.distinct_words, args.verbose, move |grid| sink.add(grid, None));
+    Ok(report_solutions(args, enumeration.solutions.len(), &enumeration.outcome, start_time.elapsed(), solver.attempts()))
+}
 
-        // Try each placement
-        for (i, (word, row, col, direction)) in new_placements.iter().enumerate() {
-            if self.attempts <= 3 && verbose {
-                println!("  {} (attempt {}). Trying '{}' at ({},{}) {:?}", i + 1, self.attempts, word, row, col, direction);
-            }
+fn report_solutions(args: &Args, found: usize, outcome: &SolveOutcome, duration: Duration, attempts: usize) -> ExitCode {
+    let (ending, exit_code) = match outcome {
+        SolveOutcome::Solved(_) => ("", ExitCode::SUCCESS),
+        SolveOutcome::Exhausted => (", no more exist", ExitCode::from(EXIT_EXHAUSTED)),
+        SolveOutcome::BudgetExceeded { .. } => (", max attempts reached", ExitCode::from(EXIT_BUDGET_EXCEEDED)),
+        SolveOutcome::Cancelled { .. } => (", cancelled", ExitCode::from(EXIT_CANCELLED)),
+    };
+    println!(
+        "\nFound {} of {} solutions in {:.2?} with {} attempts{}; saved to {}/",
+        found,
+        args.solutions,
+        duration,
+        attempts,
+        ending,
+        args.solutions_dir
+    );
+
+    if found > 0 { ExitCode::SUCCESS } else { exit_code }
+}
 
-            // Create a new grid for this attempt
-            let mut new_grid = grid.clone();
-
-            // Try the new placement
-            if new_grid.place_word(word, *row, *col, *direction, false) {  // Don't use verbose for individual validations
-                if verbose {
-                    println!("    ✅ Successfully placed '{}' at attempt {}", word, self.attempts);
-                    // Show the grid state after successful placement
-                    println!("      📋 Current grid state after placing '{}':", word);
-                    new_grid.print_grid();
-                }
-
-                // Add this placement to history
-                let mut new_history = placement_history.clone();
-                new_history.push((word.clone(), *row, *col, *direction));
-
-                // Recursively solve
-                if let Some(result) = self.dfs_solve(&mut new_grid, new_history, verbose) {
-                    if verbose {
-                        println!("🎉 Found solution with '{}' placed at ({},{}) {:?}", word, row, col, direction);
-                    }
-                    return Some(result);
-                }
-
-                if self.attempts <= 3 && verbose {
-                    println!("    ↩️ Backtracking from '{}' at attempt {}", word, self.attempts);
-                }
-            } else if self.attempts <= 3 && verbose {
-                println!("    ❌ Failed to place '{}' at attempt {}", word, self.attempts);
-            }
-        }
+/// Runs `--jobs` seeded solvers over the shared graph until `--solutions`
+/// distinct grids are found between them.
+fn solve_in_parallel(
+    args: &Args,
+    graph: &Arc<CrosswordGraph>,
+    constraints: &Constraints,
+    date: &str,
+    start_time: Instant,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let base_seed = args.seed.unwrap_or_else(rand::random);
+    println!("Running {} jobs with base seed {}", args.jobs, base_seed);
+    let make_solver = |seed| build_solver(args, graph, constraints, Some(seed), start_time);
+
+    if args.solutions > 1 {
+        fs::create_dir_all(&args.solutions_dir)?;
+        let mut sink = SolutionSink::new(args, date, &constraints.theme);
+        let portfolio = solve_portfolio(
+            args.jobs,
+            base_seed,
+            args.solutions,
+            args.distinct_words,
+            CancellationToken::new(),
+            make_solver,
+            move |seed, grid| sink.add(grid, Some(seed)),
+        )?;
+        return Ok(report_solutions(args, portfolio.solutions.len(), &portfolio.outcome, start_time.elapsed(), portfolio.attempts));
+    }
 
-        self.visited_states.insert(grid_signature);
-        None
+    let portfolio = solve_portfolio(args.jobs, base_seed, 1, false, CancellationToken::new(), make_solver, |_, _| {})?;
+    if let Some((seed, _)) = portfolio.solutions.first() {
+        println!("Found by the job with seed {} (rerun with --seed {} to reproduce it)", seed, seed);
     }
+    report_outcome(args, &constraints.theme, portfolio.outcome, start_time.elapsed(), portfolio.attempts, date)
 }
 
-// Need to add this trait for multi_cartesian_product
-use itertools::Itertools;
+fn report_outcome(
+    args: &Args,
+    theme: &Theme,
+    outcome: SolveOutcome,
+    duration: Duration,
+    attempts: usize,
+    date: &str,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let exit_code = match outcome {
+        SolveOutcome::Solved(solution) => {
+            println!("\n=== CROSSWORD SOLUTION ===");
+            solution.print_grid();
+            print_placed_words(&solution);
+            print_fill_score(&solution);
+            print_theme(&solution, theme);
+            println!("\nSolved in {:.2?} with {} attempts", duration, attempts);
+            if let (Some(dir), Some(author)) = (&args.export, &args.author) {
+                let path = export::write_puzzle(&solution, author, dir, date, args.encrypt)?;
+                println!("Exported puzzle to {}", path.display());
+            }
+            ExitCode::SUCCESS
+        }
+        SolveOutcome::Exhausted => {
+            // Only a template searched without --heuristic covers every fill;
+            // open grids prune placements and sample liner patterns.
+            if args.template.is_some() && !args.heuristic {
+                println!("No grid exists for this template: search exhausted in {:.2?} after {} attempts!", duration, attempts);
+            } else {
+                println!("Search space exhausted without finding a grid in {:.2?} after {} attempts!", duration, attempts);
+            }
+            ExitCode::from(EXIT_EXHAUSTED)
+        }
+        SolveOutcome::BudgetExceeded { best } => {
+            println!("Max attempts ({}) reached in {:.2?} without a solution. Best partial grid:", args.max_attempts, duration);
+            best.print_grid();
+            print_placed_words(&best);
+            ExitCode::from(EXIT_BUDGET_EXCEEDED)
+        }
+        SolveOutcome::Cancelled { best } => {
+            println!("Cancelled after {:.2?} and {} attempts. Best partial grid:", duration, attempts);
+            best.print_grid();
+            print_placed_words(&best);
+            ExitCode::from(EXIT_CANCELLED)
+        }
+    };
+
+    Ok(exit_code)
+}
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
+fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
     let args = Args::parse();
     
     // Load the graph
@@ -812,11 +391,33 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("Loading crossword graph...");
     }
     
-    let mut graph = CrosswordGraph::new();
-    graph.load_words(&args.input, args.verbose)?;
-    graph.find_real_intermediaries(args.verbose, args.parallel);
-    graph.build_graph(args.verbose);
-    graph.generate_liners(args.verbose);
+    let date = args.date.clone().unwrap_or_else(export::today);
+    export::check_date(&date)?;
+
+    let language = LanguageProfile::resolve(&args.lang)?;
+    let input = language.dictionary_or(args.input.as_deref())?;
+    let template = match &args.template {
+        Some(path) => Some(Template::load(path, &language)?),
+        None => None,
+    };
+    let (width, height) = match &template {
+        Some(template) => (template.width(), template.height()),
+        None => (args.width.unwrap_or(args.size), args.height.unwrap_or(args.size)),
+    };
+    if args.export.is_some() && width != height {
+        return Err(format!("the site's puzzle format needs a square grid, not {}x{}", width, height).into());
+    }
+    let config = GraphConfig {
+        language,
+        intermediaries: !args.no_intermediaries,
+        ..GraphConfig::rectangle(width, height, args.min_len)
+    };
+    let mut layers = vec![WordLayer::base(&input)];
+    layers.extend(args.layers.iter().cloned());
+    let graph = Arc::new(match &args.cache {
+        Some(cache) => CrosswordGraph::load_or_build(cache, &layers, config, args.verbose, args.parallel)?,
+        None => CrosswordGraph::build_layers(config, &layers, args.verbose, args.parallel)?,
+    });
 
     // Create directories
     fs::create_dir_all("close_calls")?;
@@ -826,27 +427,66 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("Graph loaded: {} words, {} liners", graph.words().len(), graph.liners().len());
     }
     
-    // Create solver
-    let mut solver = CrosswordSolver::new(graph, args.seed, args.max_attempts);
+    let avoided = if args.archives.is_empty() {
+        HashSet::new()
+    } else {
+        let archive = Archive::scan(&args.archives, &graph.config().language)?;
+        for skipped in &archive.skipped {
+            eprintln!("Warning: skipping {}", skipped);
+        }
+        let recent = archive.recent_words(&date, args.cooldown)?;
+        println!(
+            "Avoiding {} of the {} answers in {} published puzzles, used within {} days of {}",
+            recent.len(),
+            archive.len(),
+            archive.puzzles(),
+            args.cooldown,
+            date
+        );
+        recent
+    };
+
+    let language = &graph.config().language;
+    let mut theme = Theme::default();
+    let mut placed = Vec::new();
+    for seed_word in &args.seed_words {
+        let word = language.normalize_word(&seed_word.word);
+        match seed_word.position {
+            Some((row, col, direction)) => placed.push((word.clone(), row, col, direction)),
+            None if !graph.words().contains(&word) => {
+                return Err(format!("seed word '{}' is not a {}x{} grid word of the dictionary", word, width, height).into());
+            }
+            None => {}
+        }
+        theme.required.insert(word);
+    }
+    if let Some(path) = &args.theme_list {
+        theme.words = Theme::load_list(path, language)?;
+        theme.min = args.theme_min;
+        let usable = theme.words.iter().filter(|word| graph.words().contains(*word)).count();
+        println!(
+            "Theme list: {} of its {} words fit the grid, at least {} must land",
+            usable,
+            theme.words.len(),
+            theme.min
+        );
+    }
+    let constraints = Constraints {
+        template,
+        placed,
+        theme,
+        avoided,
+    };
     
     // Solve the crossword
     let start_time = Instant::now();
-    let solution = solver.solve(args.verbose);
-    let duration = start_time.elapsed();
-    
-    if let Some(solution) = solution {
-        println!("\n=== CROSSWORD SOLUTION ===");
-        solution.print_grid();
-        
-        println!("Placed words:");
-        for (i, word_placement) in solution.word_placements.iter().enumerate() {
-            println!("{}. {} at ({}, {}) {:?}", i + 1, word_placement.word, word_placement.row, word_placement.col, word_placement.direction);
-        }
-        
-        println!("\nSolved in {:.2?} with {} attempts", duration, solver.attempts);
-    } else {
-        println!("No solution found in {:.2?} after {} attempts!", duration, solver.attempts);
+    if args.jobs > 1 {
+        return solve_in_parallel(&args, &graph, &constraints, &date, start_time);
     }
-
-    Ok(())
+    let mut solver = build_solver(&args, &graph, &constraints, args.seed, start_time)?;
+    if args.solutions > 1 {
+        return enumerate_solutions(&args, &mut solver, &constraints.theme, &date, start_time);
+    }
+    let outcome = solver.solve(args.verbose);
+    report_outcome(&args, &constraints.theme, outcome, start_time.elapsed(), solver.attempts(), &date)
 }
@@ -0,0 +1,282 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::str::FromStr;
+
+use crate::encryption;
+use crate::grid::Direction;
+
+/// Identifies an entry: `H{row}.{k}` is the k-th run of letters in a row and
+/// `V{col}.{k}` the k-th run in a column, all counted from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ClueId {
+    pub direction: Direction,
+    pub line: usize,
+    pub index: usize,
+}
+
+impl fmt::Display for ClueId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefix = match self.direction {
+            Direction::Horizontal => 'H',
+            Direction::Vertical => 'V',
+        };
+        write!(f, "{}{}.{}", prefix, self.line, self.index)
+    }
+}
+
+impl FromStr for ClueId {
+    type Err = String;
+
+    fn from_str(id: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("'{}' is not a clue id like H1.1 or V2.1", id);
+        let direction = match id.chars().next() {
+            Some('H') => Direction::Horizontal,
+            Some('V') => Direction::Vertical,
+            _ => return Err(invalid()),
+        };
+        let (line, index) = id[1..].split_once('.').ok_or_else(invalid)?;
+        let number = |text: &str| match text.parse::<usize>() {
+            Ok(n) if n > 0 && text.chars().all(|ch| ch.is_ascii_digit()) => Ok(n),
+            _ => Err(invalid()),
+        };
+        Ok(ClueId {
+            direction,
+            line: number(line)?,
+            index: number(index)?,
+        })
+    }
+}
+
+/// A run of letters between blocks, as the web player numbers it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub id: ClueId,
+    pub answer: String,
+    pub row: usize,
+    pub col: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clue {
+    pub id: ClueId,
+    pub text: String,
+    /// Line of the puzzle file the clue was read from, 0 if it was not read
+    /// from a file.
+    pub source_line: usize,
+}
+
+/// A puzzle in the format the web player loads from `tr/` and `en/`:
+///
+/// ```text
+/// AUTHOR: Cemre
+/// GRID: 5
+/// -ASUS
+/// ...
+///
+/// H1.1: Amatörü eğlendirecek bilgisayar markası
+/// ...
+/// ```
+///
+/// Rows hold the letters as written, with `-` for blocks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    pub author: String,
+    pub rows: Vec<Vec<char>>,
+    pub clues: Vec<Clue>,
+}
+
+pub const BLOCK: char = '-';
+
+impl Puzzle {
+    /// Loads a puzzle file, decrypting it with the publish date in its
+    /// `YYYY-MM-DD.txt` name if needed.
+    pub fn load(filename: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(filename)?;
+        let date = encryption::date_from_path(filename);
+        Self::parse_dated(&content, date.as_deref()).map_err(|e| format!("{}: {}", filename, e).into())
+    }
+
+    /// Parses the file layout: the `AUTHOR:` and `GRID:` headers, a grid of
+    /// `GRID` rows of `GRID` cells, then one clue per line. `[ENCRYPTED]`
+    /// files are decrypted first. Use [`Puzzle::problems`] to check the
+    /// clues against the grid.
+    pub fn parse(content: &str) -> Result<Self, String> {
+        Self::parse_dated(content, None)
+    }
+
+    /// Like [`Puzzle::parse`], also decrypting `[ENCRYPTED:v2]` files
+    /// published on `date`.
+    pub fn parse_dated(content: &str, date: Option<&str>) -> Result<Self, String> {
+        let decrypted;
+        let content = if encryption::is_encrypted(content) {
+            decrypted = encryption::decrypt(content, date)?;
+            decrypted.as_str()
+        } else {
+            content
+        };
+
+        let mut lines = content
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty())
+            .peekable();
+
+        let (line_number, line) = lines.next().ok_or("puzzle is empty")?;
+        let author = header(line_number, line, "AUTHOR")?.to_string();
+        if author.is_empty() {
+            return Err(format!("line {}: author name is empty", line_number));
+        }
+
+        let (grid_line, line) = lines.next().ok_or("missing GRID: line")?;
+        let size = header(grid_line, line, "GRID")?;
+        let size: usize = size
+            .parse()
+            .map_err(|_| format!("line {}: GRID must be a number, not '{}'", grid_line, size))?;
+
+        let mut rows: Vec<Vec<char>> = Vec::new();
+        while let Some(&(line_number, line)) = lines.peek() {
+            if line.contains(':') {
+                break;
+            }
+            lines.next();
+
+            let row: Vec<char> = line.chars().collect();
+            if let Some(&ch) = row.iter().find(|&&ch| ch != BLOCK && !ch.is_alphanumeric()) {
+                return Err(format!("line {}: '{}' is not a letter or '{}'", line_number, ch, BLOCK));
+            }
+            if let Some(first) = rows.first() {
+                if row.len() != first.len() {
+                    return Err(format!(
+                        "line {}: row has {} cells, expected {}",
+                        line_number,
+                        row.len(),
+                        first.len()
+                    ));
+                }
+            }
+            rows.push(row);
+        }
+        let width = rows.first().map_or(0, Vec::len);
+        if rows.len() != size || width != size {
+            return Err(format!(
+                "line {}: GRID is {} but the grid is {}x{}",
+                grid_line,
+                size,
+                width,
+                rows.len()
+            ));
+        }
+
+        let mut clues: Vec<Clue> = Vec::new();
+        for (line_number, line) in lines {
+            let (id, text) = line
+                .split_once(':')
+                .ok_or_else(|| format!("line {}: expected a clue like 'H1.1: text'", line_number))?;
+            let id: ClueId = id.trim().parse().map_err(|e| format!("line {}: {}", line_number, e))?;
+            let text = text.trim();
+            if text.is_empty() {
+                return Err(format!("line {}: clue {} is empty", line_number, id));
+            }
+            if let Some(previous) = clues.iter().find(|clue| clue.id == id) {
+                return Err(format!(
+                    "line {}: clue {} is already given on line {}",
+                    line_number, id, previous.source_line
+                ));
+            }
+            clues.push(Clue {
+                id,
+                text: text.to_string(),
+                source_line: line_number,
+            });
+        }
+
+        Ok(Self { author, rows, clues })
+    }
+
+    pub fn size(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Every run of letters in the grid, across entries first, in the order
+    /// the web player numbers them.
+    pub fn entries(&self) -> Vec<Entry> {
+        let mut entries = Vec::new();
+        for row in 0..self.size() {
+            let cells: Vec<(usize, usize)> = (0..self.size()).map(|col| (row, col)).collect();
+            self.push_entries(&mut entries, Direction::Horizontal, row + 1, &cells);
+        }
+        for col in 0..self.size() {
+            let cells: Vec<(usize, usize)> = (0..self.size()).map(|row| (row, col)).collect();
+            self.push_entries(&mut entries, Direction::Vertical, col + 1, &cells);
+        }
+        entries
+    }
+
+    fn push_entries(&self, entries: &mut Vec<Entry>, direction: Direction, line: usize, cells: &[(usize, usize)]) {
+        let runs = cells
+            .split(|&(row, col)| self.rows[row][col] == BLOCK)
+            .filter(|run| !run.is_empty());
+        for (k, run) in runs.enumerate() {
+            entries.push(Entry {
+                id: ClueId { direction, line, index: k + 1 },
+                answer: run.iter().map(|&(row, col)| self.rows[row][col]).collect(),
+                row: run[0].0,
+                col: run[0].1,
+            });
+        }
+    }
+
+    pub fn clue(&self, id: ClueId) -> Option<&Clue> {
+        self.clues.iter().find(|clue| clue.id == id)
+    }
+
+    /// Clues that match no entry and entries of two or more letters that
+    /// have no clue. Single letters may go unclued.
+    pub fn problems(&self) -> Vec<String> {
+        let entries = self.entries();
+        let ids: HashSet<ClueId> = entries.iter().map(|entry| entry.id).collect();
+
+        let mut problems = Vec::new();
+        for clue in &self.clues {
+            if !ids.contains(&clue.id) {
+                problems.push(format!("line {}: clue {} has no matching entry", clue.source_line, clue.id));
+            }
+        }
+        for entry in &entries {
+            if entry.answer.chars().count() > 1 && self.clue(entry.id).is_none() {
+                problems.push(format!(
+                    "entry {} ({}) at row {}, column {} has no clue",
+                    entry.id,
+                    entry.answer,
+                    entry.row + 1,
+                    entry.col + 1
+                ));
+            }
+        }
+        problems
+    }
+}
+
+fn header<'a>(line_number: usize, line: &'a str, name: &str) -> Result<&'a str, String> {
+    line.strip_prefix(name)
+        .and_then(|rest| rest.strip_prefix(':'))
+        .map(str::trim)
+        .ok_or_else(|| format!("line {}: expected '{}: ...'", line_number, name))
+}
+
+impl fmt::Display for Puzzle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "AUTHOR: {}", self.author)?;
+        writeln!(f, "GRID: {}", self.size())?;
+        for row in &self.rows {
+            writeln!(f, "{}", row.iter().collect::<String>())?;
+        }
+        writeln!(f)?;
+        for clue in &self.clues {
+            writeln!(f, "{}: {}", clue.id, clue.text)?;
+        }
+        Ok(())
+    }
+}
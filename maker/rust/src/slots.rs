@@ -0,0 +1,266 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+use crate::grid::Direction;
+use crate::{CrosswordGraph, Word};
+
+/// An across or down entry of a line that holds a block: a run of two or
+/// more cells between blocks or the grid's edge.
+///
+/// Only lines with a block have fixed entries, as the solver fills each of
+/// their runs with one word spanning it. Lines without a block may still be
+/// split by liners and are checked as a whole instead, unless the grid
+/// started from a template, the graph was built without intermediaries or
+/// the line is no longer than the shortest word: then the whole line is a
+/// slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Slot {
+    pub direction: Direction,
+    pub row: usize,
+    pub col: usize,
+    pub length: usize,
+}
+
+impl Slot {
+    pub fn cells(&self) -> Vec<(usize, usize)> {
+        (0..self.length)
+            .map(|i| match self.direction {
+                Direction::Horizontal => (self.row, self.col + i),
+                Direction::Vertical => (self.row + i, self.col),
+            })
+            .collect()
+    }
+}
+
+/// Candidate words of every slot, pruned to arc consistency: each word left
+/// in a slot agrees with some word left in every slot crossing it.
+///
+/// Candidate sets are shared with the domains they were narrowed from until
+/// either side prunes them.
+#[derive(Debug, Clone)]
+pub struct Domains {
+    slots: Vec<Slot>,
+    letters: Vec<String>,
+    candidates: Vec<Arc<HashSet<Word>>>,
+}
+
+impl Domains {
+    /// Builds the domains of `cells` (`@` blocks, `.` empty cells and
+    /// letters), counting lines without a block as slots too when
+    /// `open_lines` returns true for their length. Words `taken` returns true
+    /// for, such as those already on the grid, are left out of slots that
+    /// still have empty cells. Returns `None` as soon as a slot has no
+    /// candidate left, which means the grid cannot be completed.
+    pub(crate) fn build(
+        cells: &[Vec<char>],
+        graph: &CrosswordGraph,
+        open_lines: impl Fn(usize) -> bool,
+        taken: impl Fn(&str) -> bool,
+    ) -> Option<Self> {
+        let slots = find_slots(cells, open_lines);
+        let mut letters = Vec::with_capacity(slots.len());
+        let mut candidates = Vec::with_capacity(slots.len());
+        for slot in &slots {
+            let pattern = slot_letters(cells, slot);
+            let domain: HashSet<Word> = if !pattern.contains('.') {
+                complete_entry(graph, &pattern)
+            } else {
+                graph
+                    .query(&pattern.replace('.', "_"))
+                    .filter(|&word| !taken(word))
+                    .map(Word::new)
+                    .collect()
+            };
+            if domain.is_empty() {
+                return None;
+            }
+            letters.push(pattern);
+            candidates.push(Arc::new(domain));
+        }
+
+        let changed = vec![true; slots.len()];
+        Self { slots, letters, candidates }.arc_consistent(&changed)
+    }
+
+    /// The domains after `placed` went on the grid these domains were built
+    /// for, leaving `cells`. While the slots stay the same, each keeps the
+    /// candidates left here that still fit its letters, without `placed`,
+    /// and propagation starts from the slots that lost some. A block that
+    /// changes the slots builds them afresh, as candidates pruned against the
+    /// old slots may fit the new ones.
+    pub(crate) fn narrow(
+        &self,
+        cells: &[Vec<char>],
+        graph: &CrosswordGraph,
+        open_lines: impl Fn(usize) -> bool,
+        taken: impl Fn(&str) -> bool,
+        placed: &str,
+    ) -> Option<Self> {
+        let slots = find_slots(cells, &open_lines);
+        if slots != self.slots {
+            return Self::build(cells, graph, open_lines, taken);
+        }
+
+        let placed = Word::new(placed);
+        let mut letters = Vec::with_capacity(slots.len());
+        let mut candidates = Vec::with_capacity(slots.len());
+        let mut changed = Vec::with_capacity(slots.len());
+        for (index, slot) in slots.iter().enumerate() {
+            let pattern = slot_letters(cells, slot);
+            let parent = &self.candidates[index];
+            let domain = if pattern == self.letters[index] {
+                if pattern.contains('.') && parent.contains(&placed) {
+                    let mut domain = HashSet::clone(parent);
+                    domain.remove(&placed);
+                    Arc::new(domain)
+                } else {
+                    Arc::clone(parent)
+                }
+            } else if !pattern.contains('.') {
+                Arc::new(complete_entry(graph, &pattern))
+            } else {
+                let fits = Word::new(&pattern.replace('.', "_"));
+                Arc::new(parent.iter().filter(|word| **word != placed && word.matches(&fits)).cloned().collect())
+            };
+            if domain.is_empty() {
+                return None;
+            }
+            changed.push(!Arc::ptr_eq(&domain, parent) && domain != *parent);
+            letters.push(pattern);
+            candidates.push(domain);
+        }
+
+        Self { slots, letters, candidates }.arc_consistent(&changed)
+    }
+
+    fn arc_consistent(mut self, changed: &[bool]) -> Option<Self> {
+        if self.make_arc_consistent(changed) {
+            Some(self)
+        } else {
+            None
+        }
+    }
+
+    pub fn slots(&self) -> &[Slot] {
+        &self.slots
+    }
+
+    pub fn candidates(&self, slot: usize) -> &HashSet<Word> {
+        &self.candidates[slot]
+    }
+
+    /// Whether `word` may go at `row`, `col` in `direction`: either the
+    /// placement fills a slot that still has `word` as a candidate, or it is
+    /// not on a slot at all.
+    pub fn allows(&self, word: &str, row: usize, col: usize, direction: Direction) -> bool {
+        let word = Word::new(word);
+        let slot = self.slots.iter().position(|slot| {
+            slot.direction == direction && slot.row == row && slot.col == col && slot.length == word.len()
+        });
+        slot.is_none_or(|slot| self.candidates[slot].contains(&word))
+    }
+
+    /// AC-3 over every pair of crossing slots, starting with the slots
+    /// crossing one that is `changed`: the words of the others kept their
+    /// support. Returns false once a domain runs empty.
+    fn make_arc_consistent(&mut self, changed: &[bool]) -> bool {
+        // Slot and position of every cell, per direction
+        let mut at_cell: HashMap<(usize, usize, Direction), (usize, usize)> = HashMap::new();
+        for (index, slot) in self.slots.iter().enumerate() {
+            for (position, (row, col)) in slot.cells().into_iter().enumerate() {
+                at_cell.insert((row, col, slot.direction), (index, position));
+            }
+        }
+
+        // Crossings of every slot: (its position, crossing slot, the crossing's position)
+        let mut crossings: Vec<Vec<(usize, usize, usize)>> = vec![Vec::new(); self.slots.len()];
+        for (&(row, col, direction), &(index, position)) in &at_cell {
+            if direction == Direction::Horizontal {
+                if let Some(&(other, other_position)) = at_cell.get(&(row, col, Direction::Vertical)) {
+                    crossings[index].push((position, other, other_position));
+                    crossings[other].push((other_position, index, position));
+                }
+            }
+        }
+
+        let mut queue: VecDeque<(usize, usize, usize, usize)> = crossings
+            .iter()
+            .enumerate()
+            .flat_map(|(index, arcs)| arcs.iter().map(move |&(position, other, other_position)| (index, position, other, other_position)))
+            .filter(|&(_, _, other, _)| changed[other])
+            .collect();
+        while let Some((index, position, other, other_position)) = queue.pop_front() {
+            let letters: HashSet<char> = self.candidates[other]
+                .iter()
+                .filter_map(|word| word.char_at(other_position))
+                .collect();
+            if self.candidates[index].iter().all(|word| word.char_at(position).is_some_and(|ch| letters.contains(&ch))) {
+                continue;
+            }
+            Arc::make_mut(&mut self.candidates[index]).retain(|word| word.char_at(position).is_some_and(|ch| letters.contains(&ch)));
+            if self.candidates[index].is_empty() {
+                return false;
+            }
+            // Slots crossing this one may have lost their support
+            for &(own_position, neighbour, neighbour_position) in &crossings[index] {
+                if neighbour != other {
+                    queue.push_back((neighbour, neighbour_position, index, own_position));
+                }
+            }
+        }
+        true
+    }
+}
+
+/// The slots of `cells`, across entries first.
+fn find_slots(cells: &[Vec<char>], open_lines: impl Fn(usize) -> bool) -> Vec<Slot> {
+    let width = cells.first().map_or(0, Vec::len);
+    let mut slots = Vec::new();
+    for (row, line) in cells.iter().enumerate() {
+        push_slots(&mut slots, line, open_lines(line.len()), |i| (row, i), Direction::Horizontal);
+    }
+    for col in 0..width {
+        let line: Vec<char> = cells.iter().map(|cells| cells[col]).collect();
+        push_slots(&mut slots, &line, open_lines(line.len()), |i| (i, col), Direction::Vertical);
+    }
+    slots
+}
+
+fn slot_letters(cells: &[Vec<char>], slot: &Slot) -> String {
+    slot.cells().iter().map(|&(row, col)| cells[row][col]).collect()
+}
+
+/// The only candidate of a slot with no empty cell: its letters, if they
+/// make a word.
+fn complete_entry(graph: &CrosswordGraph, letters: &str) -> HashSet<Word> {
+    if graph.words().contains(letters) {
+        HashSet::from([Word::new(letters)])
+    } else {
+        HashSet::new()
+    }
+}
+
+fn push_slots(
+    slots: &mut Vec<Slot>,
+    line: &[char],
+    open_lines: bool,
+    cell: impl Fn(usize) -> (usize, usize),
+    direction: Direction,
+) {
+    if !open_lines && !line.contains(&'@') {
+        return;
+    }
+    let mut start = 0;
+    for run in line.split(|&ch| ch == '@') {
+        if run.len() >= 2 {
+            let (row, col) = cell(start);
+            slots.push(Slot {
+                direction,
+                row,
+                col,
+                length: run.len(),
+            });
+        }
+        start += run.len() + 1;
+    }
+}
@@ -0,0 +1,656 @@
+use crate::grid::{CrosswordGrid, Direction};
+use crate::symmetry::Symmetry;
+use crate::template::Template;
+use crate::theme::Theme;
+use crate::CrosswordGraph;
+use rand::prelude::*;
+use rayon::prelude::*;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// How a call to [`CrosswordSolver::solve`] ended.
+#[derive(Debug, Clone)]
+pub enum SolveOutcome {
+    /// Every cell was filled.
+    Solved(CrosswordGrid),
+    /// The search space ran out without a complete grid.
+    Exhausted,
+    /// `max_attempts` was reached; `best` is the fullest grid seen.
+    BudgetExceeded { best: CrosswordGrid },
+    /// The search was cancelled or hit its deadline; `best` is the fullest
+    /// grid seen.
+    Cancelled { best: CrosswordGrid },
+}
+
+/// Result of [`CrosswordSolver::enumerate`].
+#[derive(Debug, Clone)]
+pub struct Enumeration {
+    /// Distinct solutions in the order they were found.
+    pub solutions: Vec<CrosswordGrid>,
+    /// How the search ended: `Solved` with the last solution once `limit`
+    /// were found, `Exhausted` when there are no more.
+    pub outcome: SolveOutcome,
+}
+
+/// Result of [`solve_portfolio`].
+#[derive(Debug, Clone)]
+pub struct Portfolio {
+    /// Distinct solutions in the order they were found, each with the seed
+    /// of the job that found it.
+    pub solutions: Vec<(u64, CrosswordGrid)>,
+    /// `Solved` with the last solution once `target` were found, otherwise
+    /// `Exhausted` if a job ran out of grids, or how the job with the fullest
+    /// grid stopped.
+    pub outcome: SolveOutcome,
+    /// Attempts of all jobs together.
+    pub attempts: usize,
+}
+
+/// Result of [`CrosswordSolver::find_alternatives`].
+#[derive(Debug, Clone)]
+pub enum Uniqueness {
+    /// The search space ran out without any other fill.
+    Unique,
+    /// Other complete fills of the same block layout.
+    Alternatives(Vec<CrosswordGrid>),
+    /// The budget or deadline ran out before any other fill was found.
+    Undecided,
+}
+
+/// Shared flag for stopping a running solver from another thread.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StopReason {
+    Budget,
+    Cancelled,
+}
+
+impl SolveOutcome {
+    pub fn solution(&self) -> Option<&CrosswordGrid> {
+        match self {
+            SolveOutcome::Solved(grid) => Some(grid),
+            _ => None,
+        }
+    }
+}
+
+// Multiplier of the golden-ratio sequence, which spreads job seeds apart
+const SEED_STEP: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Seed of job `job` in a portfolio started with `base_seed`. Job 0 uses
+/// the base seed itself, so one job searches exactly like a plain solver.
+pub fn job_seed(base_seed: u64, job: usize) -> u64 {
+    base_seed.wrapping_add((job as u64).wrapping_mul(SEED_STEP))
+}
+
+/// Runs `jobs` solvers in parallel, one per seed from [`job_seed`], until
+/// `target` distinct solutions are found between them. `make_solver` builds
+/// each job's solver over a shared graph from its seed; the jobs share a
+/// cancellation token that is cancelled once the target is reached, or when
+/// `cancellation` is.
+///
+/// Every job searches deterministically, so the grid a job found can be
+/// reproduced by running a single solver with that job's seed.
+/// `on_solution` is called with each new solution and its seed as soon as
+/// it is found.
+pub fn solve_portfolio<F>(
+    jobs: usize,
+    base_seed: u64,
+    target: usize,
+    distinct_words: bool,
+    cancellation: CancellationToken,
+    make_solver: F,
+    on_solution: impl FnMut(u64, &CrosswordGrid) + Send + 'static,
+) -> Result<Portfolio, String>
+where
+    F: Fn(u64) -> Result<CrosswordSolver, String> + Sync,
+{
+    let target = target.max(1);
+    let seeds: Vec<u64> = (0..jobs.max(1)).map(|job| job_seed(base_seed, job)).collect();
+    let solvers = seeds
+        .iter()
+        .map(|&seed| Ok((seed, make_solver(seed)?.with_cancellation(cancellation.clone()))))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let found: Arc<Mutex<Vec<(u64, CrosswordGrid)>>> = Arc::new(Mutex::new(Vec::new()));
+    let on_solution = Arc::new(Mutex::new(on_solution));
+    let results: Vec<(SolveOutcome, usize)> = solvers
+        .into_par_iter()
+        .map(|(seed, mut solver)| {
+            let found = Arc::clone(&found);
+            let on_solution = Arc::clone(&on_solution);
+            let cancellation = cancellation.clone();
+            // Only the shared list counts towards the target, as a job cannot
+            // tell its own solutions from those other jobs already found
+            let enumeration = solver.enumerate(usize::MAX, distinct_words, false, move |grid| {
+                let mut found = found.lock().unwrap();
+                let signature = grid.get_grid_state_signature();
+                let words = grid.entry_words();
+                let duplicate = found.iter().any(|(_, other)| {
+                    other.get_grid_state_signature() == signature || (distinct_words && other.entry_words() == words)
+                });
+                if duplicate || found.len() >= target {
+                    return;
+                }
+                found.push((seed, grid.clone()));
+                (on_solution.lock().unwrap())(seed, grid);
+                if found.len() >= target {
+                    cancellation.cancel();
+                }
+            });
+            (enumeration.outcome, solver.attempts())
+        })
+        .collect();
+
+    let solutions = std::mem::take(&mut *found.lock().unwrap());
+    let attempts = results.iter().map(|(_, attempts)| attempts).sum();
+    let outcome = match solutions.last() {
+        Some((_, last)) if solutions.len() >= target => SolveOutcome::Solved(last.clone()),
+        _ => results
+            .into_iter()
+            .map(|(outcome, _)| outcome)
+            .reduce(|kept, outcome| match (&kept, &outcome) {
+                (SolveOutcome::Exhausted, _) => kept,
+                (_, SolveOutcome::Exhausted) => outcome,
+                _ if fullest(&outcome) > fullest(&kept) => outcome,
+                _ => kept,
+            })
+            .unwrap_or(SolveOutcome::Exhausted),
+    };
+
+    Ok(Portfolio { solutions, outcome, attempts })
+}
+
+fn fullest(outcome: &SolveOutcome) -> usize {
+    match outcome {
+        SolveOutcome::BudgetExceeded { best } | SolveOutcome::Cancelled { best } => best.filled_cells,
+        _ => 0,
+    }
+}
+
+// Places a seed word on the starting grid, refusing words that run off it
+fn place_seed_word(start: &mut CrosswordGrid, word: &str, row: usize, col: usize, direction: Direction) -> Result<(), String> {
+    let length = word.chars().count();
+    let (last_row, last_col) = match direction {
+        Direction::Horizontal => (row, col + length.saturating_sub(1)),
+        Direction::Vertical => (row + length.saturating_sub(1), col),
+    };
+    if last_row >= start.height() || last_col >= start.width() {
+        return Err(format!(
+            "'{}' at ({}, {}) {:?} runs off the {}x{} grid",
+            word,
+            row,
+            col,
+            direction,
+            start.width(),
+            start.height()
+        ));
+    }
+    if !start.place_word(word, row, col, direction, false) {
+        return Err(format!("cannot place '{}' at ({}, {}) {:?}", word, row, col, direction));
+    }
+    Ok(())
+}
+
+// Called with every solution as soon as it is found
+type SolutionCallback = Box<dyn FnMut(&CrosswordGrid) + Send>;
+
+pub struct CrosswordSolver {
+    graph: Arc<CrosswordGraph>,
+    rng: StdRng,
+    attempts: usize,
+    max_attempts: usize,
+    visited_states: HashSet<String>,
+    best: Option<CrosswordGrid>,
+    stop_reason: Option<StopReason>,
+    cancellation: CancellationToken,
+    deadline: Option<Instant>,
+    start: Option<CrosswordGrid>,
+    symmetry: Symmetry,
+    // Complete grids found so far; the search stops at `solution_limit`
+    solutions: Vec<CrosswordGrid>,
+    solution_limit: usize,
+    // Signatures of complete grids that do not count as solutions
+    excluded: HashSet<String>,
+    // Also treat solutions with the same set of words as duplicates
+    distinct_words: bool,
+    on_solution: Option<SolutionCallback>,
+    // Branch on the most constrained entry instead of every placement
+    heuristic: bool,
+    // Complete grids with a lower average word score are dead ends
+    min_score: Option<f64>,
+    // Words no placement may use
+    avoided: Arc<HashSet<String>>,
+    // Words complete grids must use
+    theme: Theme,
+    // Save nearly complete grids as the search goes
+    save_progress: bool,
+}
+
+impl CrosswordSolver {
+    /// Takes the graph itself or an `Arc` of it, so that several solvers can
+    /// share one graph.
+    pub fn new(graph: impl Into<Arc<CrosswordGraph>>, seed: Option<u64>, max_attempts: usize) -> Self {
+        let graph = graph.into();
+        let rng = if let Some(seed) = seed {
+            StdRng::seed_from_u64(seed)
+        } else {
+            StdRng::from_entropy()
+        };
+
+        Self {
+            graph,
+            rng,
+            attempts: 0,
+            max_attempts,
+            visited_states: HashSet::new(),
+            best: None,
+            stop_reason: None,
+            cancellation: CancellationToken::new(),
+            deadline: None,
+            start: None,
+            symmetry: Symmetry::None,
+            solutions: Vec::new(),
+            solution_limit: 1,
+            excluded: HashSet::new(),
+            distinct_words: false,
+            on_solution: None,
+            heuristic: false,
+            min_score: None,
+            avoided: Arc::default(),
+            theme: Theme::default(),
+            save_progress: false,
+        }
+    }
+
+    /// Fills `template` instead of an empty grid, keeping its blocks and
+    /// pre-filled letters. Words already placed go on the template too;
+    /// fails if one does not fit there.
+    pub fn with_template(mut self, template: &Template) -> Result<Self, String> {
+        let mut start = CrosswordGrid::from_template(self.graph.clone(), template)?;
+        start.set_symmetry(self.symmetry)?;
+        for placement in self.start.take().map(|grid| grid.word_placements).unwrap_or_default() {
+            place_seed_word(&mut start, &placement.word.to_string(), placement.row, placement.col, placement.direction)?;
+        }
+        self.start = Some(start);
+        Ok(self)
+    }
+
+    /// Keeps the blocks of the grid symmetric. Fails if the template's blocks
+    /// already break `symmetry`.
+    pub fn with_symmetry(mut self, symmetry: Symmetry) -> Result<Self, String> {
+        let mut start = match self.start.take() {
+            Some(start) => start,
+            None => CrosswordGrid::new(self.graph.clone()),
+        };
+        start.set_symmetry(symmetry)?;
+        self.symmetry = symmetry;
+        self.start = Some(start);
+        Ok(self)
+    }
+
+    /// Stops the search, returning [`SolveOutcome::Cancelled`], once `token`
+    /// is cancelled.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = token;
+        self
+    }
+
+    /// Stops the search, returning [`SolveOutcome::Cancelled`], once
+    /// `deadline` has passed.
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Tries only the placements of the entry with the fewest left, least
+    /// constraining words first, instead of every placement in random order.
+    pub fn with_heuristic(mut self, heuristic: bool) -> Self {
+        self.heuristic = heuristic;
+        self
+    }
+
+    /// Rejects complete grids whose average word score is below
+    /// `min_score`, searching on for a better fill.
+    pub fn with_min_score(mut self, min_score: Option<f64>) -> Self {
+        self.min_score = min_score;
+        self
+    }
+
+    /// Never places any of `words`, e.g. answers published recently. Letters
+    /// of the template are kept even if they spell one.
+    pub fn with_avoided_words(mut self, words: HashSet<String>) -> Self {
+        self.avoided = Arc::new(words);
+        self
+    }
+
+    /// Places `word` before filling, on top of any template. Fails if it
+    /// runs off the grid or does not fit there.
+    pub fn with_placed_word(mut self, word: &str, row: usize, col: usize, direction: Direction) -> Result<Self, String> {
+        let mut start = match self.start.take() {
+            Some(start) => start,
+            None => CrosswordGrid::new(self.graph.clone()),
+        };
+        place_seed_word(&mut start, word, row, col, direction)?;
+        self.start = Some(start);
+        Ok(self)
+    }
+
+    /// Rejects complete grids that miss a required word of `theme` or use
+    /// too few of its list, and tries the words it still needs first.
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Saves nearly complete grids to `solvables/` and `close_calls/` in the
+    /// working directory during the search, see
+    /// [`CrosswordGrid::set_save_progress`].
+    pub fn with_progress_files(mut self, save_progress: bool) -> Self {
+        self.save_progress = save_progress;
+        self
+    }
+
+    pub fn attempts(&self) -> usize {
+        self.attempts
+    }
+
+    pub fn solve(&mut self, verbose: bool) -> SolveOutcome {
+        let mut grid = self
+            .start
+            .clone()
+            .unwrap_or_else(|| CrosswordGrid::new(self.graph.clone()));
+        grid.set_avoided_words(Arc::clone(&self.avoided));
+        grid.set_save_progress(self.save_progress);
+        self.attempts = 0;
+        self.visited_states.clear();
+        self.best = None;
+        self.stop_reason = None;
+        self.solutions.clear();
+
+        if verbose {
+            println!("🚀 Starting DFS crossword generation...");
+            println!("📊 Graph statistics:");
+            println!("   - Total words: {}", self.graph.words().len());
+            println!("   - {}-letter words (priority): {}", grid.width, self.graph.words().iter().filter(|w| w.chars().count() == grid.width).count());
+            println!("   - Shorter words: {}", self.graph.words().iter().filter(|w| w.chars().count() < grid.width).count());
+            println!("   - Total liners: {}", self.graph.liners().len());
+            println!("   - Max attempts: {}", self.max_attempts);
+            println!("📏 Grid size: {}x{}", grid.width, grid.height);
+            println!("🎯 Strategy: Prioritize {}-letter words, use padded liners for shorter words", grid.width);
+        }
+
+        let result = self.dfs_solve(&mut grid, Vec::new(), verbose);
+
+        match result {
+            Some(solution) => {
+                if verbose {
+                    println!("\n🎉 SOLUTION FOUND after {} attempts!", self.attempts);
+                }
+                SolveOutcome::Solved(solution)
+            }
+            None if self.stop_reason == Some(StopReason::Budget) => {
+                if verbose {
+                    println!("\n⏱️ Max attempts ({}) reached. Stopping.", self.max_attempts);
+                }
+                SolveOutcome::BudgetExceeded { best: self.best.take().unwrap_or(grid) }
+            }
+            None if self.stop_reason == Some(StopReason::Cancelled) => {
+                if verbose {
+                    println!("\n🛑 Cancelled after {} attempts. Stopping.", self.attempts);
+                }
+                SolveOutcome::Cancelled { best: self.best.take().unwrap_or(grid) }
+            }
+            None => {
+                if verbose {
+                    println!("\n😞 No solution found after {} attempts.", self.attempts);
+                }
+                SolveOutcome::Exhausted
+            }
+        }
+    }
+
+    /// Keeps searching after the first solution until `limit` distinct ones
+    /// are found, calling `on_solution` with each as soon as it is found.
+    /// Solutions are distinct by grid, and by set of words with
+    /// `distinct_words`.
+    pub fn enumerate(
+        &mut self,
+        limit: usize,
+        distinct_words: bool,
+        verbose: bool,
+        on_solution: impl FnMut(&CrosswordGrid) + Send + 'static,
+    ) -> Enumeration {
+        self.solution_limit = limit.max(1);
+        self.distinct_words = distinct_words;
+        self.on_solution = Some(Box::new(on_solution));
+
+        let outcome = self.solve(verbose);
+        let solutions = std::mem::take(&mut self.solutions);
+        self.solution_limit = 1;
+        self.distinct_words = false;
+        self.on_solution = None;
+
+        Enumeration { solutions, outcome }
+    }
+
+    /// Searches for up to `limit` complete fills of `solution`'s block layout
+    /// other than `solution` itself. Any template given to the solver is
+    /// replaced by that layout.
+    pub fn find_alternatives(&mut self, solution: &CrosswordGrid, limit: usize, verbose: bool) -> Uniqueness {
+        self.start = Some(solution.block_layout());
+        self.excluded = HashSet::from([solution.get_grid_state_signature()]);
+        self.solution_limit = limit.max(1);
+
+        let outcome = self.solve(verbose);
+        let alternatives = std::mem::take(&mut self.solutions);
+        self.excluded.clear();
+        self.solution_limit = 1;
+
+        match outcome {
+            _ if !alternatives.is_empty() => Uniqueness::Alternatives(alternatives),
+            SolveOutcome::Exhausted => Uniqueness::Unique,
+            _ => Uniqueness::Undecided,
+        }
+    }
+
+    /// Checks the attempt budget, cancellation token and deadline, remembering
+    /// why the search has to stop.
+    fn should_stop(&mut self) -> bool {
+        if self.stop_reason.is_none() {
+            if self.attempts > self.max_attempts {
+                self.stop_reason = Some(StopReason::Budget);
+            } else if self.cancellation.is_cancelled()
+                || self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+            {
+                self.stop_reason = Some(StopReason::Cancelled);
+            }
+        }
+        self.stop_reason.is_some()
+    }
+
+    /// Records a complete grid. Returns whether the search should stop with
+    /// it, i.e. whether `solution_limit` new solutions have been found.
+    fn accept_solution(&mut self, grid: &CrosswordGrid) -> bool {
+        let signature = grid.get_grid_state_signature();
+        if self.excluded.contains(&signature) || self.solutions.iter().any(|found| found.get_grid_state_signature() == signature) {
+            return false;
+        }
+        if self.distinct_words {
+            let words = grid.entry_words();
+            if self.solutions.iter().any(|found| found.entry_words() == words) {
+                return false;
+            }
+        }
+
+        if let Some(on_solution) = self.on_solution.as_mut() {
+            on_solution(grid);
+        }
+        self.solutions.push(grid.clone());
+        self.solutions.len() >= self.solution_limit
+    }
+
+    fn record_best(&mut self, grid: &CrosswordGrid) {
+        let is_better = self
+            .best
+            .as_ref()
+            .is_none_or(|best| grid.filled_cells > best.filled_cells);
+        if is_better {
+            self.best = Some(grid.clone());
+        }
+    }
+
+    fn dfs_solve(&mut self, grid: &mut CrosswordGrid, placement_history: Vec<(String, usize, usize, Direction)>, verbose: bool) -> Option<CrosswordGrid> {
+        self.attempts += 1;
+
+        if self.should_stop() {
+            return None;
+        }
+        self.record_best(grid);
+
+        // Create grid state signature
+        let grid_signature = grid.get_grid_state_signature();
+        if self.visited_states.contains(&grid_signature) {
+            return None;
+        }
+
+        // Show progress
+        if verbose && (self.attempts <= 3 || self.attempts.is_multiple_of(25)) {
+            println!("\n🔄 Attempt {} - {} words placed", self.attempts, grid.word_placements.len());
+            grid.print_grid();
+        }
+
+        // Check if grid is complete
+        if grid.is_complete() {
+            if let Some(min_score) = self.min_score {
+                let score = grid.fill_score().map_or(0.0, |score| score.average);
+                if score < min_score {
+                    if verbose {
+                        println!("❌ Average word score {:.1} is below {}. Backtracking...", score, min_score);
+                    }
+                    return None;
+                }
+            }
+            if !self.theme.is_met(grid) {
+                if verbose {
+                    println!("❌ Theme words missing from the grid. Backtracking...");
+                }
+                return None;
+            }
+            if !self.accept_solution(grid) {
+                return None;
+            }
+            if verbose {
+                println!("\n🎉 SOLUTION FOUND! Grid is complete after {} attempts!", self.attempts);
+                println!("Final grid:");
+                grid.print_grid();
+            }
+            return Some(grid.clone());
+        }
+
+        // Get possible word placements
+        let mut placements = if self.heuristic {
+            grid.get_heuristic_placements(&mut self.rng)
+        } else {
+            grid.get_possible_placements(&mut self.rng)
+        };
+        if !self.theme.is_empty() {
+            if self.heuristic && !self.theme.is_met(grid) {
+                // The most constrained entry may take no theme word, so try
+                // the theme words of every entry first
+                let mut wanted = self.theme.wanted(grid.get_possible_placements(&mut self.rng), grid);
+                wanted.retain(|placement| !placements.contains(placement));
+                wanted.append(&mut placements);
+                placements = wanted;
+            }
+            self.theme.prioritize(&mut placements, grid);
+        }
+
+        if placements.is_empty() {
+            if verbose {
+                println!("❌ No valid placements found at attempt {}. Backtracking...", self.attempts);
+            }
+            return None;
+        }
+
+        // Filter out placements we've already tried in this branch
+        let new_placements: Vec<_> = placements
+            .into_iter()
+            .filter(|placement| !placement_history.contains(placement))
+            .collect();
+
+        if new_placements.is_empty() {
+            if verbose {
+                println!("❌ No new placements available at attempt {}. Backtracking...", self.attempts);
+            }
+            return None;
+        }
+
+        if self.attempts <= 3 && verbose {
+            println!("Trying {} new placements at attempt {}...", new_placements.len(), self.attempts);
+        }
+
+        // Try each placement
+        for (i, (word, row, col, direction)) in new_placements.iter().enumerate() {
+            if self.attempts <= 3 && verbose {
+                println!("  {} (attempt {}). Trying '{}' at ({},{}) {:?}", i + 1, self.attempts, word, row, col, direction);
+            }
+
+            // Create a new grid for this attempt
+            let mut new_grid = grid.clone();
+
+            // Try the new placement
+            if new_grid.place_word(word, *row, *col, *direction, false) {  // Don't use verbose for individual validations
+                if verbose {
+                    println!("    ✅ Successfully placed '{}' at attempt {}", word, self.attempts);
+                    // Show the grid state after successful placement
+                    println!("      📋 Current grid state after placing '{}':", word);
+                    new_grid.print_grid();
+                }
+
+                // Add this placement to history
+                let mut new_history = placement_history.clone();
+                new_history.push((word.clone(), *row, *col, *direction));
+
+                // Recursively solve
+                if let Some(result) = self.dfs_solve(&mut new_grid, new_history, verbose) {
+                    if verbose {
+                        println!("🎉 Found solution with '{}' placed at ({},{}) {:?}", word, row, col, direction);
+                    }
+                    return Some(result);
+                }
+
+                if self.should_stop() {
+                    return None;
+                }
+
+                if self.attempts <= 3 && verbose {
+                    println!("    ↩️ Backtracking from '{}' at attempt {}", word, self.attempts);
+                }
+            } else if self.attempts <= 3 && verbose {
+                println!("    ❌ Failed to place '{}' at attempt {}", word, self.attempts);
+            }
+        }
+
+        self.visited_states.insert(grid_signature);
+        None
+    }
+}
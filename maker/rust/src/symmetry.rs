@@ -0,0 +1,65 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Symmetry the black squares of a grid must keep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Symmetry {
+    #[default]
+    None,
+    /// 180° rotation about the centre, the usual rule for mini crosswords.
+    Rotational,
+    /// Mirror across the horizontal axis (top half matches bottom half).
+    Horizontal,
+    /// Mirror across the vertical axis (left half matches right half).
+    Vertical,
+    /// Mirror across the main diagonal; square grids only.
+    Diagonal,
+}
+
+impl Symmetry {
+    /// Cell that must match `(row, col)` in a `width` x `height` grid, or
+    /// `None` when there is no constraint.
+    pub fn mirror(&self, row: usize, col: usize, width: usize, height: usize) -> Option<(usize, usize)> {
+        match self {
+            Symmetry::None => None,
+            Symmetry::Rotational => Some((height - 1 - row, width - 1 - col)),
+            Symmetry::Horizontal => Some((height - 1 - row, col)),
+            Symmetry::Vertical => Some((row, width - 1 - col)),
+            Symmetry::Diagonal => Some((col, row)),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Symmetry::None => "none",
+            Symmetry::Rotational => "rotational",
+            Symmetry::Horizontal => "horizontal",
+            Symmetry::Vertical => "vertical",
+            Symmetry::Diagonal => "diagonal",
+        }
+    }
+}
+
+impl FromStr for Symmetry {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "none" => Ok(Symmetry::None),
+            "rotational" => Ok(Symmetry::Rotational),
+            "horizontal" => Ok(Symmetry::Horizontal),
+            "vertical" => Ok(Symmetry::Vertical),
+            "diagonal" => Ok(Symmetry::Diagonal),
+            _ => Err(format!(
+                "unknown symmetry '{}': expected none, rotational, horizontal, vertical or diagonal",
+                name
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
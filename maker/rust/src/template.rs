@@ -0,0 +1,96 @@
+use std::fs;
+
+use crate::language::LanguageProfile;
+
+/// A grid shape to fill: black squares, pre-filled letters and empty cells.
+///
+/// Template files have one line per row. `#` or `-` marks a black square,
+/// `.` or `_` an empty cell, and any letter of the language is kept as a
+/// fixed, pre-filled cell:
+///
+/// ```text
+/// ..#..
+/// .....
+/// #.K.#
+/// .....
+/// ..#..
+/// ```
+///
+/// Cells are stored the way the grid stores them: `@` for blocks, `.` for
+/// empty cells and lowercase letters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    rows: Vec<Vec<char>>,
+}
+
+impl Template {
+    pub fn load(filename: &str, language: &LanguageProfile) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(filename)?;
+        Self::parse(&content, language).map_err(|e| format!("{}: {}", filename, e).into())
+    }
+
+    pub fn parse(content: &str, language: &LanguageProfile) -> Result<Self, String> {
+        let mut rows: Vec<Vec<char>> = Vec::new();
+
+        for (index, line) in content.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let mut row = Vec::new();
+            for ch in line.chars() {
+                match ch {
+                    '#' | '-' => row.push('@'),
+                    '.' | '_' => row.push('.'),
+                    _ => {
+                        let letters = language.normalize_word(&ch.to_string());
+                        let mut letters = letters.chars();
+                        match (letters.next(), letters.next()) {
+                            (Some(letter), None) if language.is_valid_word(&letter.to_string()) => {
+                                row.push(letter)
+                            }
+                            _ => {
+                                return Err(format!(
+                                    "line {}: '{}' is not a block, an empty cell or a {} letter",
+                                    line_number, ch, language.code
+                                ))
+                            }
+                        }
+                    }
+                }
+            }
+
+            if let Some(first) = rows.first() {
+                if row.len() != first.len() {
+                    return Err(format!(
+                        "line {}: row has {} cells, expected {}",
+                        line_number,
+                        row.len(),
+                        first.len()
+                    ));
+                }
+            }
+            rows.push(row);
+        }
+
+        if rows.is_empty() {
+            return Err("template has no rows".to_string());
+        }
+
+        Ok(Self { rows })
+    }
+
+    pub fn width(&self) -> usize {
+        self.rows[0].len()
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn rows(&self) -> &[Vec<char>] {
+        &self.rows
+    }
+}
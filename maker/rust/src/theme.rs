@@ -0,0 +1,124 @@
+use std::collections::HashSet;
+use std::fs;
+use std::str::FromStr;
+
+use itertools::Itertools;
+
+use crate::grid::{CrosswordGrid, Direction};
+use crate::language::LanguageProfile;
+
+type Placement = (String, usize, usize, Direction);
+
+/// Words a fill has to use: every `required` word, and at least `min` of
+/// `words`, e.g. a list of cities for a city-themed puzzle.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Theme {
+    pub required: HashSet<String>,
+    pub words: HashSet<String>,
+    pub min: usize,
+}
+
+impl Theme {
+    /// Reads a theme list, one word per line, normalized the way `language`
+    /// normalizes dictionary words. Anything after a `;` is ignored, so
+    /// scored dictionaries can be used as lists too.
+    pub fn load_list(path: &str, language: &LanguageProfile) -> Result<HashSet<String>, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        Ok(content
+            .lines()
+            .map(|line| line.split(';').next().unwrap_or(line))
+            .map(|word| language.normalize_word(word))
+            .filter(|word| !word.is_empty())
+            .collect())
+    }
+
+    /// Whether the theme puts no constraint on the fill.
+    pub fn is_empty(&self) -> bool {
+        self.required.is_empty() && self.min == 0
+    }
+
+    /// Entries of `grid` that are required or theme list words, sorted.
+    pub fn landed(&self, grid: &CrosswordGrid) -> Vec<String> {
+        let mut landed: Vec<String> = grid
+            .entry_words()
+            .into_iter()
+            .filter(|word| self.required.contains(word) || self.words.contains(word))
+            .collect();
+        landed.dedup();
+        landed
+    }
+
+    /// Whether `grid` uses every required word and enough theme list words.
+    pub fn is_met(&self, grid: &CrosswordGrid) -> bool {
+        let entries = grid.entry_words();
+        self.required.iter().all(|word| entries.contains(word)) && self.list_entries(&entries) >= self.min
+    }
+
+    /// Moves the placements that get `grid` closer to the theme to the
+    /// front, keeping the order within both parts.
+    pub(crate) fn prioritize(&self, placements: &mut [Placement], grid: &CrosswordGrid) {
+        let wants = self.wants(grid);
+        placements.sort_by_key(|(word, _, _, _)| !wants(word));
+    }
+
+    /// The placements that get `grid` closer to the theme.
+    pub(crate) fn wanted(&self, placements: Vec<Placement>, grid: &CrosswordGrid) -> Vec<Placement> {
+        let wants = self.wants(grid);
+        placements.into_iter().filter(|(word, _, _, _)| wants(word)).collect()
+    }
+
+    // Whether a word is required and not on the grid yet, or on the list
+    // while too few of it are
+    fn wants(&self, grid: &CrosswordGrid) -> impl Fn(&str) -> bool + '_ {
+        let entries = grid.entry_words();
+        let short = self.list_entries(&entries) < self.min;
+        move |word| {
+            (self.required.contains(word) && !entries.iter().any(|entry| entry == word)) || (short && self.words.contains(word))
+        }
+    }
+
+    // Distinct theme list words among `entries`, which are sorted
+    fn list_entries(&self, entries: &[String]) -> usize {
+        entries.iter().dedup().filter(|word| self.words.contains(*word)).count()
+    }
+}
+
+/// A `--seed-word` value: `WORD`, which the fill has to use somewhere, or
+/// `WORD@row,col,dir` with `dir` `h` or `v`, placed there before filling.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeedWord {
+    pub word: String,
+    pub position: Option<(usize, usize, Direction)>,
+}
+
+impl FromStr for SeedWord {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid seed word '{}': expected WORD or WORD@row,col,h|v", text);
+        let (word, position) = match text.split_once('@') {
+            Some((word, position)) => {
+                let parts: Vec<&str> = position.split(',').map(str::trim).collect();
+                let [row, col, direction] = parts[..] else {
+                    return Err(invalid());
+                };
+                let row = row.parse().map_err(|_| invalid())?;
+                let col = col.parse().map_err(|_| invalid())?;
+                let direction = match direction {
+                    "h" | "H" => Direction::Horizontal,
+                    "v" | "V" => Direction::Vertical,
+                    _ => return Err(invalid()),
+                };
+                (word, Some((row, col, direction)))
+            }
+            None => (text, None),
+        };
+        if word.trim().is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            word: word.trim().to_string(),
+            position,
+        })
+    }
+}
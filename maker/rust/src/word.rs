@@ -0,0 +1,62 @@
+use std::fmt;
+
+/// A word stored as Unicode characters.
+///
+/// Lengths and positions count letters, not UTF-8 bytes, so Turkish words
+/// such as "şerit" are five letters long like their ASCII neighbours.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Word(Vec<char>);
+
+impl Word {
+    pub fn new(text: &str) -> Self {
+        Self(text.chars().collect())
+    }
+
+    /// Number of letters in the word.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn chars(&self) -> &[char] {
+        &self.0
+    }
+
+    pub fn char_at(&self, index: usize) -> Option<char> {
+        self.0.get(index).copied()
+    }
+
+    /// Whether the word fits a pattern where `_` matches any letter.
+    pub fn matches(&self, pattern: &Word) -> bool {
+        self.len() == pattern.len()
+            && self
+                .0
+                .iter()
+                .zip(pattern.0.iter())
+                .all(|(&w_char, &p_char)| p_char == '_' || w_char == p_char)
+    }
+}
+
+impl From<&str> for Word {
+    fn from(text: &str) -> Self {
+        Self::new(text)
+    }
+}
+
+impl FromIterator<char> for Word {
+    fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl fmt::Display for Word {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for ch in &self.0 {
+            write!(f, "{}", ch)?;
+        }
+        Ok(())
+    }
+}
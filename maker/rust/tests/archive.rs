@@ -0,0 +1,43 @@
+use crossword_grapher::{encryption, Archive, CrosswordSolver, LanguageProfile, SolveOutcome};
+use std::collections::HashSet;
+
+mod common;
+
+const PUZZLE: &str = "AUTHOR: Test\nGRID: 2\nAB\nBA\n\nH1.1: First (AB)\nH2.1: Second (BA)\nV1.1: Third (AB)\nV2.1: Fourth (BA)\n";
+
+#[test]
+fn answers_are_collected_with_their_dates() {
+    let dir = common::temp_path("archive");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("2025-07-01.txt"), PUZZLE).unwrap();
+    std::fs::write(dir.join("2025-08-01.txt"), encryption::encrypt(&PUZZLE.replace("BA", "BC"))).unwrap();
+    std::fs::write(dir.join("2025-09-01.txt"), "not a puzzle").unwrap();
+    std::fs::write(dir.join("notes.txt"), "not a puzzle either").unwrap();
+
+    let archive = Archive::scan(&[dir.to_str().unwrap().to_string()], &LanguageProfile::english()).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(archive.puzzles(), 2);
+    assert_eq!(archive.skipped.len(), 1);
+    assert_eq!(archive.last_used("ab"), Some("2025-08-01"));
+    assert_eq!(archive.last_used("ba"), Some("2025-07-01"));
+    assert_eq!(archive.last_used("bc"), Some("2025-08-01"));
+
+    let recent = archive.recent_words("2025-07-20", 20).unwrap();
+    assert_eq!(recent, HashSet::from(["ab".to_string(), "ba".to_string(), "bc".to_string()]));
+    let recent = archive.recent_words("2025-06-01", 40).unwrap();
+    assert_eq!(recent, HashSet::from(["ab".to_string(), "ba".to_string()]));
+    assert!(archive.recent_words("2026-07-01", 30).unwrap().is_empty());
+}
+
+#[test]
+fn avoided_words_stay_off_the_grid() {
+    let graph = common::graph(&["ab", "bc", "ba", "cb", "ac", "ca"]);
+    let avoided = HashSet::from(["ab".to_string()]);
+    let mut solver = CrosswordSolver::new(graph, Some(7), 10_000).with_avoided_words(avoided);
+    let enumeration = solver.enumerate(100, false, false, |_| {});
+    assert!(matches!(enumeration.outcome, SolveOutcome::Exhausted));
+    let mut found: Vec<String> = enumeration.solutions.iter().map(|grid| grid.get_grid_state_signature()).collect();
+    found.sort();
+    assert_eq!(found, ["cabc", "cbac"]);
+}
@@ -0,0 +1,33 @@
+use crossword_grapher::{CrosswordGraph, GraphConfig, WordLayer};
+
+mod common;
+
+const DICTIONARY: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../turkish_words.txt");
+
+#[test]
+fn saved_graph_loads_back_identically() {
+    let config = GraphConfig::square(4, 4);
+    let graph = CrosswordGraph::build(config.clone(), DICTIONARY, false, false).unwrap();
+
+    let path = common::temp_path("cache").with_extension("bin");
+    let path = path.to_str().unwrap();
+    graph.save(path).unwrap();
+    let loaded = CrosswordGraph::load(path).unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    assert_eq!(loaded.config(), graph.config());
+    assert_eq!(loaded.words(), graph.words());
+    assert_eq!(loaded.words_that_can_be_checked_against(), graph.words_that_can_be_checked_against());
+    assert_eq!(loaded.real_intermediaries(), graph.real_intermediaries());
+    assert_eq!(loaded.liners(), graph.liners());
+    assert_eq!(loaded.get_words_for_pattern("ağ__"), graph.get_words_for_pattern("ağ__"));
+    assert_eq!(loaded.cache_key(), graph.cache_key());
+    assert_eq!(graph.cache_key(), CrosswordGraph::cache_key_for(&[WordLayer::base(DICTIONARY)], &config).unwrap());
+}
+
+#[test]
+fn cache_key_changes_with_config() {
+    let four = CrosswordGraph::cache_key_for(&[WordLayer::base(DICTIONARY)], &GraphConfig::square(4, 4)).unwrap();
+    let five = CrosswordGraph::cache_key_for(&[WordLayer::base(DICTIONARY)], &GraphConfig::square(5, 4)).unwrap();
+    assert_ne!(four, five);
+}
@@ -0,0 +1,74 @@
+use crossword_grapher::check::{check_puzzle, LengthPolicy, Verdict};
+use crossword_grapher::Puzzle;
+
+mod common;
+use common::word_lists;
+
+const PUZZLE: &str = "AUTHOR: Jones McGyle
+GRID: 3
+AB-
+CAT
+-RO
+
+H1.1: a
+H2.1: b
+H3.1: c
+V1.1: d
+V2.1: e
+V3.1: f
+";
+
+#[test]
+fn entries_get_verdicts_per_policy() {
+    let puzzle = Puzzle::parse(PUZZLE).unwrap();
+    let graph = word_lists(&["ab", "cat", "ro", "bar", "to"]);
+
+    let verdicts = |policy: &LengthPolicy| -> Vec<(String, Verdict)> {
+        check_puzzle(&puzzle, &graph, policy, 0)
+            .into_iter()
+            .map(|report| (report.entry.id.to_string(), report.verdict))
+            .collect()
+    };
+
+    let default = verdicts(&LengthPolicy::default());
+    assert_eq!(
+        default,
+        [
+            ("H1.1".to_string(), Verdict::Word),
+            ("H2.1".to_string(), Verdict::Word),
+            ("H3.1".to_string(), Verdict::Word),
+            ("V1.1".to_string(), Verdict::Unknown),
+            ("V2.1".to_string(), Verdict::Word),
+            ("V3.1".to_string(), Verdict::Word),
+        ]
+    );
+
+    let strict = LengthPolicy {
+        min_length: 3,
+        ..LengthPolicy::default()
+    };
+    assert_eq!(verdicts(&strict)[0].1, Verdict::TooShort);
+    assert_eq!(verdicts(&strict)[1].1, Verdict::Word);
+}
+
+#[test]
+fn suggestions_keep_letters_of_valid_crossings() {
+    let puzzle = Puzzle::parse(PUZZLE).unwrap();
+    // "AC" is unknown and both its letters are crossed by valid words, so
+    // no word fits; the closest words of its length are suggested instead
+    let graph = word_lists(&["ab", "cat", "ro", "bar", "to", "at", "ax", "xc"]);
+    let report = check_puzzle(&puzzle, &graph, &LengthPolicy::default(), 4)
+        .into_iter()
+        .find(|report| report.verdict == Verdict::Unknown)
+        .unwrap();
+    assert_eq!(report.word, "ac");
+    assert_eq!(report.suggestions, ["ab", "at", "ax", "xc"]);
+
+    // Without a valid crossing on C, words keeping the A come first
+    let graph = word_lists(&["ab", "ro", "bar", "to", "at", "ax", "xc"]);
+    let report = check_puzzle(&puzzle, &graph, &LengthPolicy::default(), 5)
+        .into_iter()
+        .find(|report| report.entry.id.to_string() == "V1.1")
+        .unwrap();
+    assert_eq!(report.suggestions, ["ab", "at", "ax"]);
+}
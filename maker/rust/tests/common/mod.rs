@@ -0,0 +1,76 @@
+// Fixtures shared by the integration tests. Each test binary uses only some
+// of them.
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crossword_grapher::{CrosswordGraph, GraphConfig, LanguageProfile};
+
+static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+/// A path under the temp directory that no other test, in this or another
+/// test binary, uses.
+pub fn temp_path(name: &str) -> PathBuf {
+    let n = NEXT.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!("crossword-{}-{}-{}", name, std::process::id(), n))
+}
+
+/// A file of `lines` under the temp directory, removed when dropped.
+pub struct TempFile(PathBuf);
+
+impl TempFile {
+    pub fn new(lines: &[&str]) -> Self {
+        let path = temp_path("words").with_extension("txt");
+        std::fs::write(&path, lines.join("\n")).unwrap();
+        Self(path)
+    }
+
+    pub fn path(&self) -> &str {
+        self.0.to_str().unwrap()
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+impl AsRef<Path> for TempFile {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+/// English graph config for a `width` x `height` grid of words of two or
+/// more letters.
+pub fn config(width: usize, height: usize) -> GraphConfig {
+    GraphConfig {
+        language: LanguageProfile::english(),
+        ..GraphConfig::rectangle(width, height, 2)
+    }
+}
+
+/// Builds the graph of `words` for `config`.
+pub fn graph_for(config: GraphConfig, words: &[&str]) -> CrosswordGraph {
+    let file = TempFile::new(words);
+    CrosswordGraph::build(config, file.path(), false, false).unwrap()
+}
+
+/// English graph of `words` for a 2x2 grid.
+pub fn graph(words: &[&str]) -> CrosswordGraph {
+    graph_for(config(2, 2), words)
+}
+
+/// English word lists of `words`, without liners, as `crossword check`
+/// loads them.
+pub fn word_lists(words: &[&str]) -> CrosswordGraph {
+    let file = TempFile::new(words);
+    let mut graph = CrosswordGraph::new(GraphConfig {
+        language: LanguageProfile::english(),
+        ..GraphConfig::default()
+    });
+    graph.load_words(file.path(), false).unwrap();
+    graph
+}
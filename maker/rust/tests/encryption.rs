@@ -0,0 +1,101 @@
+use crossword_grapher::encryption::{decrypt, encrypt, encrypt_v2, format_of, is_encrypted, xor, Format};
+use crossword_grapher::Puzzle;
+use std::fs;
+use std::process::Command;
+
+mod common;
+
+const ARCHIVE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../..");
+
+fn archive() -> Vec<(String, String)> {
+    let mut files = Vec::new();
+    for lang in ["tr", "en"] {
+        for entry in fs::read_dir(format!("{}/{}", ARCHIVE, lang)).unwrap() {
+            let path = entry.unwrap().path();
+            files.push((path.display().to_string(), fs::read_to_string(&path).unwrap()));
+        }
+    }
+    files
+}
+
+#[test]
+fn every_archive_file_round_trips() {
+    let files = archive();
+    assert!(files.iter().any(|(_, content)| is_encrypted(content)));
+    assert!(files.iter().any(|(_, content)| !is_encrypted(content)));
+
+    for (path, content) in files {
+        if is_encrypted(&content) {
+            // Re-encrypting the decrypted text must give the published bytes back
+            let plain = decrypt(&content, None).unwrap();
+            assert_eq!(encrypt(&plain), content, "{}", path);
+            Puzzle::parse(&plain).unwrap_or_else(|e| panic!("{}: {}", path, e));
+        } else {
+            assert_eq!(decrypt(&encrypt(&content), None).unwrap(), content.trim(), "{}", path);
+        }
+    }
+}
+
+#[test]
+fn xor_matches_simple_encrypt() {
+    // simpleEncrypt("AUTHOR: Ğ\n") in the browser
+    assert_eq!(xor("AUTHOR: Ğ\n"), "k\u{7f}~bex\u{10}\n\u{134} ");
+    // Characters outside the BMP are XORed per surrogate, like JavaScript
+    assert_eq!(xor(&xor("🧩")), "🧩");
+    assert!(decrypt("AUTHOR: Cemre", None).is_err());
+}
+
+#[test]
+fn v2_round_trips_with_the_publish_date_only() {
+    for (path, content) in archive() {
+        let plain = decrypt(&content, None).unwrap_or_else(|_| content.trim().to_string());
+        let encrypted = encrypt_v2(&plain, "2025-07-06").unwrap();
+        assert_eq!(format_of(&encrypted), Some(Format::V2));
+        assert!(!encrypted.contains(&plain[..20]), "{}", path);
+        assert_eq!(decrypt(&encrypted, Some("2025-07-06")).unwrap(), plain, "{}", path);
+        assert!(decrypt(&encrypted, Some("2025-07-07")).is_err());
+        assert!(decrypt(&encrypted, None).is_err());
+    }
+    // A fresh nonce per puzzle
+    assert_ne!(encrypt_v2("AUTHOR: Cemre", "2025-07-06"), encrypt_v2("AUTHOR: Cemre", "2025-07-06"));
+}
+
+#[test]
+fn v2_decrypts_webcrypto_output() {
+    // Encrypted with crypto.subtle (HKDF + AES-GCM) using nonce 00 01 .. 0b
+    let encrypted = "[ENCRYPTED:v2]\nAAECAwQFBgcICQoLJXOX6EYOZ+Mf5nJCuO1YDYYoLgj/7D1TmCWqLmjd/3u70wlu1HUGVHQkIq5cFDZIRvJj";
+    assert_eq!(decrypt(encrypted, Some("2025-07-06")).unwrap(), "AUTHOR: Cemre\nGRID: 3\nTAŞ\nALO\nSİM");
+}
+
+#[test]
+fn the_web_player_decrypts_v2_files_from_the_cli() {
+    let plain = "AUTHOR: Cemre\nGRID: 3\nTAŞ\nALO\nSİM";
+    let dir = common::temp_path("v2");
+    fs::create_dir_all(&dir).unwrap();
+    let (input, output) = (dir.join("puzzle.txt"), dir.join("2025-07-06.txt"));
+    fs::write(&input, plain).unwrap();
+
+    let status = Command::new(env!("CARGO_BIN_EXE_crossword"))
+        .args(["encrypt", "--format", "v2"])
+        .arg(&input)
+        .arg("-o")
+        .arg(&output)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    // processPuzzleText keys v2 with the date the player loaded the file for
+    let script = "console.log = () => {};\n\
+        const { processPuzzleText } = require(process.argv[1]);\n\
+        const text = require('fs').readFileSync(process.argv[2], 'utf8');\n\
+        processPuzzleText(text, '2025-07-06').then(s => process.stdout.write(s));";
+    let loader = concat!(env!("CARGO_MANIFEST_DIR"), "/../../static/js/puzzle-loader.js");
+    let node = Command::new("node").arg("-e").arg(script).arg(loader).arg(&output).output();
+    fs::remove_dir_all(&dir).unwrap();
+    let Ok(node) = node else {
+        eprintln!("node not found, skipping the web player check");
+        return;
+    };
+    assert!(node.status.success(), "{}", String::from_utf8_lossy(&node.stderr));
+    assert_eq!(String::from_utf8(node.stdout).unwrap(), plain);
+}
@@ -0,0 +1,59 @@
+use crossword_grapher::solver::{job_seed, solve_portfolio};
+use crossword_grapher::{CancellationToken, CrosswordSolver, SolveOutcome};
+use std::sync::{mpsc, Arc};
+
+mod common;
+use common::graph;
+
+const WORDS: [&str; 6] = ["ab", "bc", "ba", "cb", "ac", "ca"];
+
+#[test]
+fn every_fill_is_streamed_once() {
+    let (sender, receiver) = mpsc::channel();
+    let mut solver = CrosswordSolver::new(graph(&WORDS), Some(7), 10_000);
+    let enumeration = solver.enumerate(100, false, false, move |grid| {
+        sender.send(grid.get_grid_state_signature()).unwrap();
+    });
+
+    assert!(matches!(enumeration.outcome, SolveOutcome::Exhausted));
+    let streamed: Vec<String> = receiver.iter().collect();
+    let mut found: Vec<String> = enumeration.solutions.iter().map(|grid| grid.get_grid_state_signature()).collect();
+    assert_eq!(streamed, found);
+    found.sort();
+    assert_eq!(found, ["abca", "acba", "bacb", "bcab", "cabc", "cbac"]);
+}
+
+#[test]
+fn limit_and_word_sets_cut_the_list() {
+    let mut solver = CrosswordSolver::new(graph(&WORDS), Some(7), 10_000);
+    let enumeration = solver.enumerate(3, false, false, |_| {});
+    assert!(matches!(enumeration.outcome, SolveOutcome::Solved(_)));
+    assert_eq!(enumeration.solutions.len(), 3);
+
+    // abca and acba, bacb and bcab, cabc and cbac use the same words
+    let enumeration = solver.enumerate(100, true, false, |_| {});
+    let mut word_sets: Vec<Vec<String>> = enumeration.solutions.iter().map(|grid| grid.entry_words()).collect();
+    assert_eq!(word_sets.len(), 3);
+    word_sets.sort();
+    word_sets.dedup();
+    assert_eq!(word_sets.len(), 3);
+}
+
+#[test]
+fn portfolio_jobs_share_one_list() {
+    let graph = Arc::new(graph(&WORDS));
+    let make_solver = |seed| Ok(CrosswordSolver::new(Arc::clone(&graph), Some(seed), 10_000));
+    assert_eq!(job_seed(42, 0), 42);
+
+    let portfolio = solve_portfolio(4, 42, 100, false, CancellationToken::new(), make_solver, |_, _| {}).unwrap();
+    assert!(matches!(portfolio.outcome, SolveOutcome::Exhausted));
+    let mut found: Vec<String> = portfolio.solutions.iter().map(|(_, grid)| grid.get_grid_state_signature()).collect();
+    found.sort();
+    assert_eq!(found, ["abca", "acba", "bacb", "bcab", "cabc", "cbac"]);
+
+    let portfolio = solve_portfolio(4, 42, 3, false, CancellationToken::new(), make_solver, |_, _| {}).unwrap();
+    assert!(matches!(portfolio.outcome, SolveOutcome::Solved(_)));
+    assert_eq!(portfolio.solutions.len(), 3);
+    let seeds: Vec<u64> = (0..4).map(|job| job_seed(42, job)).collect();
+    assert!(portfolio.solutions.iter().all(|(seed, _)| seeds.contains(seed)));
+}
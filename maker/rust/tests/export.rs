@@ -0,0 +1,55 @@
+use crossword_grapher::export::{add_days, check_date, puzzle_text};
+use crossword_grapher::{CrosswordGraph, CrosswordGrid, GraphConfig, LanguageProfile, Template};
+
+const PUZZLE: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/../../tr/2025-07-06.txt"));
+
+fn solved_grid(puzzle: &str) -> CrosswordGrid {
+    let language = LanguageProfile::turkish();
+    let rows: Vec<&str> = puzzle.lines().skip(2).take_while(|line| !line.is_empty()).collect();
+    let template = Template::parse(&rows.join("\n"), &language).unwrap();
+    let config = GraphConfig {
+        language,
+        ..GraphConfig::square(rows.len(), 1)
+    };
+    CrosswordGrid::from_template(CrosswordGraph::new(config), &template).unwrap()
+}
+
+#[test]
+fn exported_grid_and_clue_ids_match_the_archive() {
+    let exported = puzzle_text(&solved_grid(PUZZLE), "Cemre").unwrap();
+
+    let (header, clues) = exported.split_once("\n\n").unwrap();
+    let (expected_header, expected_clues) = PUZZLE.split_once("\n\n").unwrap();
+    assert_eq!(header, expected_header);
+
+    let ids = |text: &str| -> Vec<String> {
+        text.lines()
+            .filter_map(|line| line.split_once(": ").map(|(id, _)| id.to_string()))
+            .collect()
+    };
+    assert_eq!(ids(clues), ids(expected_clues));
+    assert!(clues.contains("H4.1: TODO (BİRA)"));
+    assert!(clues.contains("V1.2: TODO (K)"));
+}
+
+#[test]
+fn incomplete_grids_and_bad_dates_are_rejected() {
+    let rows = ["-ASUS", "ORAYA", "HAKAN", "-BİRA", "KANI."];
+    let language = LanguageProfile::turkish();
+    let template = Template::parse(&rows.join("\n"), &language).unwrap();
+    let config = GraphConfig {
+        language,
+        ..GraphConfig::square(5, 1)
+    };
+    let grid = CrosswordGrid::from_template(CrosswordGraph::new(config), &template).unwrap();
+    assert!(puzzle_text(&grid, "Cemre").is_err());
+
+    assert!(check_date("2025-07-06").is_ok());
+    assert!(check_date("2025-7-6").is_err());
+    assert!(check_date("2025-13-01").is_err());
+
+    assert_eq!(add_days("2025-07-06", 0).unwrap(), "2025-07-06");
+    assert_eq!(add_days("2025-07-30", 3).unwrap(), "2025-08-02");
+    assert_eq!(add_days("2024-02-28", 1).unwrap(), "2024-02-29");
+    assert_eq!(add_days("2025-12-31", 1).unwrap(), "2026-01-01");
+}
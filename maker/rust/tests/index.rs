@@ -0,0 +1,38 @@
+use std::collections::HashSet;
+
+use crossword_grapher::{CrosswordSolver, GraphConfig, PatternIndex};
+
+mod common;
+
+#[test]
+fn queries_and_the_letters_of_a_pattern() {
+    let words: HashSet<String> = ["kalem", "kanat", "kamer", "sakal", "kedi", "şerit"].iter().map(|w| w.to_string()).collect();
+    let index = PatternIndex::new(&words);
+
+    let query = |pattern: &str| index.query(pattern).collect::<Vec<_>>();
+    assert_eq!(query("ka___"), ["kalem", "kamer", "kanat"]);
+    assert_eq!(query("_a__t"), ["kanat"]);
+    assert_eq!(query("_____"), ["kalem", "kamer", "kanat", "sakal", "şerit"]);
+    assert_eq!(query("ş___t"), ["şerit"]);
+    assert_eq!(query("kedi"), ["kedi"]);
+    assert!(query("z____").is_empty());
+    assert!(query("k_____").is_empty());
+}
+
+#[test]
+fn graphs_without_intermediaries_fill_whole_lines() {
+    let config = GraphConfig {
+        intermediaries: false,
+        ..common::config(2, 2)
+    };
+    let graph = common::graph_for(config, &["ab", "bc", "ba", "cb"]);
+
+    assert!(graph.real_intermediaries().is_empty());
+    assert!(graph.liners().is_empty());
+    assert_eq!(graph.query("_b").collect::<Vec<_>>(), ["ab", "cb"]);
+
+    let mut solver = CrosswordSolver::new(graph, Some(3), 1000);
+    let solution = solver.solve(false).solution().cloned().unwrap();
+    assert!(solution.is_complete());
+    assert_eq!(solution.entry_words().len(), 4);
+}
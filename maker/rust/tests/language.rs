@@ -0,0 +1,41 @@
+use crossword_grapher::{LanguageProfile, Locale};
+
+#[test]
+fn builtin_profiles_match_site_language_codes() {
+    let tr = LanguageProfile::resolve("tr").unwrap();
+    assert_eq!(tr.locale, Locale::Turkish);
+    assert!(tr.is_valid_word("şerit"));
+    assert!(!tr.is_valid_word("wax"));
+
+    let en = LanguageProfile::resolve("en").unwrap();
+    assert!(en.is_valid_word("wax"));
+    assert!(!en.is_valid_word("şerit"));
+}
+
+#[test]
+fn custom_profile_folds_digraphs_into_one_cell() {
+    let profile = LanguageProfile::parse(
+        "# Dutch\ncode = nl\nalphabet = abcdefghijklmnopqrstuvwxyzĳ\ncase = en\ndigraph = ij ĳ\n",
+    )
+    .unwrap();
+
+    assert_eq!(profile.dictionary, "../nl_words.txt");
+    assert_eq!(profile.normalize_word("IJS"), "ĳs");
+    assert_eq!(profile.expand_digraphs("ĳs"), "ijs");
+    assert!(profile.is_valid_word("ĳs"));
+}
+
+#[test]
+fn profile_errors_report_line_numbers() {
+    let err = LanguageProfile::parse("code = nl\nalphabet\n").unwrap_err();
+    assert!(err.starts_with("line 2:"), "{}", err);
+}
+
+#[test]
+fn missing_default_dictionary_asks_for_input() {
+    let mut profile = LanguageProfile::english();
+    profile.dictionary = "no/such/words.txt".to_string();
+    let err = profile.dictionary_or(None).unwrap_err();
+    assert!(err.contains("--input"), "{}", err);
+    assert_eq!(profile.dictionary_or(Some("words.txt")).unwrap(), "words.txt");
+}
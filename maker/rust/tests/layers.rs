@@ -0,0 +1,52 @@
+use crossword_grapher::{CrosswordGraph, LayerKind, WordLayer};
+
+mod common;
+use common::TempFile;
+
+#[test]
+fn later_layers_add_and_remove_words() {
+    let base = TempFile::new(&["abcd", "bcda", "cdab", "dabc"]);
+    let slang = TempFile::new(&["abab", "abcd"]);
+    let blocked = TempFile::new(&["cdab", "abab", "zzzz"]);
+    let layers = [
+        WordLayer::base(base.path()),
+        WordLayer::new(LayerKind::Add, slang.path()).with_label("slang"),
+        WordLayer::new(LayerKind::Remove, blocked.path()),
+    ];
+    let config = common::config(4, 4);
+
+    let mut graph = CrosswordGraph::new(config.clone());
+    let counts: Vec<(usize, usize)> = layers
+        .iter()
+        .map(|layer| graph.load_layer(layer, false).unwrap())
+        .map(|counts| (counts.added, counts.removed))
+        .collect();
+    assert_eq!(counts, [(4, 0), (1, 0), (0, 2)]);
+
+    let graph = CrosswordGraph::build_layers(config.clone(), &layers, false, false).unwrap();
+    let mut words: Vec<&String> = graph.words().iter().collect();
+    words.sort();
+    assert_eq!(words, ["abcd", "bcda", "dabc"]);
+    assert!(graph.words_that_can_be_checked_against().contains("cdab"));
+    assert!(!graph.real_intermediaries().iter().any(|intermediary| intermediary.starts_with("cd")));
+
+    // Moving a list from one kind of layer to another invalidates the cache
+    let swapped = [
+        WordLayer::base(base.path()),
+        WordLayer::new(LayerKind::Remove, slang.path()),
+        WordLayer::new(LayerKind::Add, blocked.path()),
+    ];
+    let key = CrosswordGraph::cache_key_for(&layers, &config).unwrap();
+    assert_eq!(key, graph.cache_key());
+    assert_ne!(key, CrosswordGraph::cache_key_for(&swapped, &config).unwrap());
+}
+
+#[test]
+fn layers_parse_from_the_command_line() {
+    let layer: WordLayer = "remove:recent=used.txt".parse().unwrap();
+    assert_eq!(layer, WordLayer::new(LayerKind::Remove, "used.txt").with_label("recent"));
+    let layer: WordLayer = "add:slang.txt".parse().unwrap();
+    assert_eq!((layer.kind, layer.label.as_str()), (LayerKind::Add, "slang.txt"));
+    assert!("block:words.txt".parse::<WordLayer>().is_err());
+    assert!("add:".parse::<WordLayer>().is_err());
+}
@@ -0,0 +1,25 @@
+use crossword_grapher::Locale;
+
+#[test]
+fn turkish_keeps_dotted_and_dotless_i_apart() {
+    let tr = Locale::Turkish;
+    assert_eq!(tr.to_lowercase("ŞERİT"), "şerit");
+    assert_eq!(tr.to_lowercase("KANIT"), "kanıt");
+    assert_eq!(tr.to_lowercase("I\u{0307}NCİ"), "inci");
+    assert_eq!(tr.to_uppercase("şerit"), "ŞERİT");
+    assert_eq!(tr.to_uppercase("kanıt"), "KANIT");
+}
+
+#[test]
+fn english_uses_default_case_mapping() {
+    let en = Locale::English;
+    assert_eq!(en.to_uppercase("alo"), "ALO");
+    assert_eq!(en.to_lowercase("IRON"), "iron");
+}
+
+#[test]
+fn language_codes_match_the_site() {
+    assert_eq!("tr".parse::<Locale>(), Ok(Locale::Turkish));
+    assert_eq!(Locale::from_code("en-US"), Some(Locale::English));
+    assert!("de".parse::<Locale>().is_err());
+}
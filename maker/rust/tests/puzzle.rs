@@ -0,0 +1,68 @@
+use crossword_grapher::puzzle::ClueId;
+use crossword_grapher::Puzzle;
+use std::fs;
+
+const ARCHIVE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../..");
+
+const PUZZLE: &str = "AUTHOR: Jones McGyle
+GRID: 3
+AB-
+C-D
+EFG
+
+H1.1: First
+H2.1: Letter
+H2.2: Another
+H3.1: Last
+V1.1: Down
+V2.1: Up
+V3.1: Side
+";
+
+#[test]
+fn archive_puzzles_decrypt_and_parse_without_problems() {
+    let mut checked = 0;
+    for lang in ["tr", "en"] {
+        for entry in fs::read_dir(format!("{}/{}", ARCHIVE, lang)).unwrap() {
+            let path = entry.unwrap().path();
+            let content = fs::read_to_string(&path).unwrap();
+            let puzzle = Puzzle::parse(&content).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+            assert_eq!(puzzle.problems(), Vec::<String>::new(), "{}", path.display());
+            checked += 1;
+        }
+    }
+    assert!(checked > 0);
+}
+
+#[test]
+fn entries_are_numbered_like_the_web_player() {
+    let puzzle = Puzzle::parse(PUZZLE).unwrap();
+    let ids: Vec<String> = puzzle.entries().iter().map(|entry| entry.id.to_string()).collect();
+    assert_eq!(ids, ["H1.1", "H2.1", "H2.2", "H3.1", "V1.1", "V2.1", "V2.2", "V3.1"]);
+    assert_eq!(puzzle.clue("H2.2".parse::<ClueId>().unwrap()).unwrap().text, "Another");
+    assert_eq!(Puzzle::parse(&puzzle.to_string()).unwrap().entries(), puzzle.entries());
+    // V2.2 is the single letter "F", which may go unclued
+    assert!(puzzle.problems().is_empty());
+}
+
+#[test]
+fn layout_errors_report_line_numbers() {
+    let error = |content: String| Puzzle::parse(&content).unwrap_err();
+    assert_eq!(error(PUZZLE.replace("C-D", "C-DX")), "line 4: row has 4 cells, expected 3");
+    assert_eq!(error(PUZZLE.replace("GRID: 3", "GRID: 4")), "line 2: GRID is 4 but the grid is 3x3");
+    assert_eq!(error(PUZZLE.replace("EFG\n", "")), "line 2: GRID is 3 but the grid is 3x2");
+    assert_eq!(error(PUZZLE.replace("V2.1", "V1.1")), "line 12: clue V1.1 is already given on line 11");
+    assert_eq!(error(PUZZLE.replace("H1.1:", "X1.1:")), "line 7: 'X1.1' is not a clue id like H1.1 or V2.1");
+}
+
+#[test]
+fn unmatched_clues_and_unclued_entries_are_problems() {
+    let puzzle = Puzzle::parse(&PUZZLE.replace("H3.1: Last", "H3.2: Last")).unwrap();
+    assert_eq!(
+        puzzle.problems(),
+        [
+            "line 10: clue H3.2 has no matching entry",
+            "entry H3.1 (EFG) at row 3, column 1 has no clue",
+        ]
+    );
+}
@@ -0,0 +1,24 @@
+use crossword_grapher::{CrosswordSolver, SolveOutcome};
+
+mod common;
+
+#[test]
+fn rows_and_columns_take_words_of_their_own_length() {
+    let graph = common::graph_for(common::config(3, 2), &["abc", "bcd", "ab", "bc", "cd", "abcd"]);
+
+    // Too long for either direction
+    assert!(!graph.words().contains("abcd"));
+    let mut lengths: Vec<usize> = graph.liners().iter().map(|liner| liner.chars().count()).collect();
+    lengths.sort();
+    lengths.dedup();
+    assert_eq!(lengths, [2, 3]);
+
+    let mut solver = CrosswordSolver::new(graph, Some(7), 10_000);
+    let enumeration = solver.enumerate(100, false, false, |_| {});
+    assert!(matches!(enumeration.outcome, SolveOutcome::Exhausted));
+    let found: Vec<String> = enumeration.solutions.iter().map(|grid| grid.get_grid_state_signature()).collect();
+    assert_eq!(found, ["abcbcd"]);
+    let grid = &enumeration.solutions[0];
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+    assert_eq!(grid.entry_words(), ["ab", "abc", "bc", "bcd", "cd"]);
+}
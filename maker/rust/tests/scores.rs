@@ -0,0 +1,33 @@
+use crossword_grapher::{CrosswordGraph, CrosswordSolver, SolveOutcome, DEFAULT_WORD_SCORE};
+
+mod common;
+use common::{graph, TempFile};
+
+#[test]
+fn scores_are_read_from_the_dictionary() {
+    let graph = graph(&["ab;90", "BA ; 80", "bc", "cb;10"]);
+    assert!(graph.has_scores());
+    assert_eq!(graph.word_score("ab"), 90);
+    assert_eq!(graph.word_score("ba"), 80);
+    assert_eq!(graph.word_score("bc"), DEFAULT_WORD_SCORE);
+    assert_eq!(graph.word_score("cb"), 10);
+
+    let file = TempFile::new(&["ab;90", "ba;high"]);
+    let error = CrosswordGraph::build(common::config(2, 2), file.path(), false, false).unwrap_err();
+    assert!(error.to_string().contains("line 2"), "{}", error);
+}
+
+#[test]
+fn grids_below_the_minimum_score_are_rejected() {
+    let graph = graph(&["ab;90", "ba;80", "ac;70", "ca;60", "bc;10", "cb;10"]);
+    let mut solver = CrosswordSolver::new(graph, Some(7), 10_000).with_min_score(Some(60.0));
+    let enumeration = solver.enumerate(100, false, false, |_| {});
+
+    // Only abca and acba avoid bc and cb
+    assert!(matches!(enumeration.outcome, SolveOutcome::Exhausted));
+    let mut found: Vec<String> = enumeration.solutions.iter().map(|grid| grid.get_grid_state_signature()).collect();
+    found.sort();
+    assert_eq!(found, ["abca", "acba"]);
+    let score = enumeration.solutions[0].fill_score().unwrap();
+    assert_eq!((score.average, score.minimum), (75.0, 60));
+}
@@ -0,0 +1,115 @@
+use crossword_grapher::{CrosswordGraph, CrosswordGrid, CrosswordSolver, Direction, Domains, LanguageProfile, Slot, SolveOutcome, Template, Word};
+use rand::prelude::*;
+
+mod common;
+
+// Blocks on the diagonal: the across entries of rows 0 and 2 and the down
+// entries of columns 0 and 2 are slots, and they cross at (0, 2) and (2, 0)
+const LAYOUT: &str = "#..\n.#.\n..#";
+const WORDS: [&str; 4] = ["ab", "bc", "ca", "xy"];
+
+fn setup(words: &[&str], rows: &str) -> (CrosswordGraph, Template) {
+    let template = Template::parse(rows, &LanguageProfile::english()).unwrap();
+    let graph = common::graph_for(common::config(template.width(), template.height()), words);
+    (graph, template)
+}
+
+#[test]
+fn crossings_prune_candidates() {
+    let (graph, template) = setup(&WORDS, LAYOUT);
+    let domains = CrosswordGrid::from_template(graph, &template).unwrap().domains().unwrap();
+    let first = Slot {
+        direction: Direction::Horizontal,
+        row: 0,
+        col: 1,
+        length: 2,
+    };
+    let index = domains.slots().iter().position(|slot| *slot == first).unwrap();
+    assert_eq!(domains.slots().len(), 4);
+
+    // No word starts with "y", so "xy" cannot cross the down entry at (0, 2)
+    let mut candidates: Vec<String> = domains.candidates(index).iter().map(Word::to_string).collect();
+    candidates.sort();
+    assert_eq!(candidates, ["ab", "bc", "ca"]);
+    assert!(!domains.allows("xy", 0, 1, Direction::Horizontal));
+}
+
+#[test]
+fn an_empty_domain_ends_the_search() {
+    // The "x" forces "xy" across, and no down entry can start with its "y"
+    let (graph, template) = setup(&WORDS, "#x.\n.#.\n..#");
+    assert!(CrosswordGrid::from_template(graph.clone(), &template).unwrap().domains().is_none());
+
+    let mut solver = CrosswordSolver::new(graph, Some(1), 1000).with_template(&template).unwrap();
+    assert!(matches!(solver.solve(false), SolveOutcome::Exhausted));
+    assert_eq!(solver.attempts(), 1);
+}
+
+#[test]
+fn the_heuristic_branches_on_one_entry() {
+    // The four slots need four different words, so add a fifth
+    let (graph, template) = setup(&["ab", "bc", "ca", "xy", "ac"], LAYOUT);
+    let grid = CrosswordGrid::from_template(graph.clone(), &template).unwrap();
+    let placements = grid.get_heuristic_placements(&mut StdRng::seed_from_u64(1));
+    assert!(!placements.is_empty());
+    assert!(placements.iter().all(|&(_, row, col, direction)| (row, col, direction) == (placements[0].1, placements[0].2, placements[0].3)));
+    assert!(placements.iter().all(|(word, _, _, _)| word != "xy"));
+
+    let mut solver = CrosswordSolver::new(graph, Some(1), 1000).with_template(&template).unwrap().with_heuristic(true);
+    let solution = solver.solve(false).solution().cloned().unwrap();
+    assert!(solution.is_complete());
+}
+
+#[test]
+fn a_placement_keeps_the_domains_it_checked() {
+    let (graph, template) = setup(&WORDS, LAYOUT);
+    let mut grid = CrosswordGrid::from_template(graph, &template).unwrap();
+    assert!(grid.place_word("ab", 0, 1, Direction::Horizontal, false));
+
+    // Built once by the check, then shared by every later lookup
+    let domains = grid.domains().unwrap();
+    assert!(std::sync::Arc::ptr_eq(&domains, &grid.domains().unwrap()));
+    let down = domains.slots().iter().position(|slot| (slot.direction, slot.row, slot.col) == (Direction::Vertical, 0, 2)).unwrap();
+    let candidates: Vec<String> = domains.candidates(down).iter().map(Word::to_string).collect();
+    assert_eq!(candidates, ["bc"]);
+}
+
+#[test]
+fn open_lines_are_slots_when_only_a_spanning_word_fits() {
+    let words = ["abc", "bca", "cab", "ab", "bc"];
+    let mut config = common::config(3, 3);
+
+    // Two-letter words may still leave blocks in any line
+    let grid = CrosswordGrid::new(common::graph_for(config.clone(), &words));
+    assert!(grid.domains().unwrap().slots().is_empty());
+
+    config.min_word_length = 3;
+    let grid = CrosswordGrid::new(common::graph_for(config, &words));
+    assert_eq!(grid.domains().unwrap().slots().len(), 6);
+}
+
+#[test]
+fn narrowed_domains_match_a_fresh_build() {
+    let mut config = common::config(3, 3);
+    config.min_word_length = 3;
+    let graph = common::graph_for(config, &["abc", "def", "ghi", "adg", "beh", "cfi", "dxy", "bxz"]);
+    let mut grid = CrosswordGrid::new(graph);
+    assert!(grid.place_word("abc", 0, 0, Direction::Horizontal, false));
+    assert!(grid.place_word("adg", 0, 0, Direction::Vertical, false));
+
+    let candidates = |domains: &Domains| -> Vec<Vec<String>> {
+        (0..domains.slots().len())
+            .map(|slot| {
+                let mut words: Vec<String> = domains.candidates(slot).iter().map(Word::to_string).collect();
+                words.sort();
+                words
+            })
+            .collect()
+    };
+    let narrowed = candidates(&grid.domains().unwrap());
+    grid.set_avoided_words(Default::default());
+    assert_eq!(narrowed, candidates(&grid.domains().unwrap()));
+    // Only "def" across fits the "f" of "cfi" down, which leaves "beh"
+    assert_eq!(narrowed[1], ["def"]);
+    assert_eq!(narrowed[4], ["beh"]);
+}
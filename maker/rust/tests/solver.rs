@@ -0,0 +1,47 @@
+use crossword_grapher::{CancellationToken, CrosswordGraph, CrosswordGrid, CrosswordSolver, SolveOutcome};
+use std::time::{Duration, Instant};
+
+mod common;
+
+// Rows and columns of a 3x3 fill need at least three placements
+fn graph() -> CrosswordGraph {
+    common::graph_for(common::config(3, 3), &["abc", "bca", "cab", "acb", "bac", "cba"])
+}
+
+#[test]
+fn a_tiny_budget_is_exceeded_not_exhausted() {
+    let mut solver = CrosswordSolver::new(graph(), Some(7), 2);
+    let outcome = solver.solve(false);
+    let SolveOutcome::BudgetExceeded { best } = outcome else {
+        panic!("expected the budget to run out, got {:?}", outcome);
+    };
+    assert!(best.filled_cells() > 0);
+    assert!(!best.is_complete());
+
+    // The same search with room to run finds a fill
+    let mut solver = CrosswordSolver::new(graph(), Some(7), 10_000);
+    assert!(matches!(solver.solve(false), SolveOutcome::Solved(_)));
+}
+
+// The fullest grid a cancelled search saw
+fn cancelled(outcome: SolveOutcome) -> CrosswordGrid {
+    let SolveOutcome::Cancelled { best } = outcome else {
+        panic!("expected the search to be cancelled, got {:?}", outcome);
+    };
+    best
+}
+
+#[test]
+fn a_cancelled_token_or_a_past_deadline_stops_the_search() {
+    let token = CancellationToken::new();
+    token.cancel();
+    let mut solver = CrosswordSolver::new(graph(), Some(7), 10_000).with_cancellation(token);
+    let best = cancelled(solver.solve(false));
+    assert!(!best.is_complete());
+    assert_eq!(solver.attempts(), 1);
+
+    let mut solver = CrosswordSolver::new(graph(), Some(7), 10_000).with_deadline(Instant::now() - Duration::from_secs(1));
+    let best = cancelled(solver.solve(false));
+    assert!(!best.is_complete());
+    assert_eq!(solver.attempts(), 1);
+}
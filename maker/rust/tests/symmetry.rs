@@ -0,0 +1,73 @@
+use crossword_grapher::{CrosswordGraph, CrosswordGrid, CrosswordSolver, Direction, SolveOutcome, Symmetry};
+
+mod common;
+
+const MODES: [Symmetry; 4] = [Symmetry::Rotational, Symmetry::Horizontal, Symmetry::Vertical, Symmetry::Diagonal];
+
+fn graph() -> CrosswordGraph {
+    common::graph_for(common::config(3, 3), &["abc", "cab", "bca", "ab", "ba", "bc", "cb", "ca", "ac", "aa", "bb"])
+}
+
+fn blocks(grid: &CrosswordGrid) -> Vec<(usize, usize)> {
+    let cells = grid.cells().iter().enumerate().flat_map(|(row, cells)| cells.iter().enumerate().map(move |(col, &cell)| (row, col, cell)));
+    cells.filter(|&(_, _, cell)| cell == '@').map(|(row, col, _)| (row, col)).collect()
+}
+
+#[test]
+fn mirror_cells_of_a_rectangle() {
+    // 4 wide and 3 high
+    assert_eq!(Symmetry::Rotational.mirror(0, 1, 4, 3), Some((2, 2)));
+    assert_eq!(Symmetry::Horizontal.mirror(0, 1, 4, 3), Some((2, 1)));
+    assert_eq!(Symmetry::Vertical.mirror(0, 1, 4, 3), Some((0, 2)));
+    assert_eq!(Symmetry::Rotational.mirror(1, 3, 4, 3), Some((1, 0)));
+    assert_eq!(Symmetry::None.mirror(0, 1, 4, 3), None);
+}
+
+#[test]
+fn diagonal_symmetry_needs_a_square_grid() {
+    let mut grid = CrosswordGrid::new(CrosswordGraph::new(common::config(3, 2)));
+    assert!(grid.set_symmetry(Symmetry::Diagonal).is_err());
+    assert!(grid.set_symmetry(Symmetry::Rotational).is_ok());
+}
+
+#[test]
+fn placements_mirror_their_blocks() {
+    // The block closing off "ab" at (0, 2) is mirrored; vertically its
+    // mirror is the "a" itself, so the placement is refused
+    let expected = [Some(vec![(0, 2), (2, 0)]), Some(vec![(0, 2), (2, 2)]), None, Some(vec![(0, 2), (2, 0)])];
+    for (symmetry, expected) in MODES.into_iter().zip(expected) {
+        let mut grid = CrosswordGrid::new(graph());
+        grid.set_symmetry(symmetry).unwrap();
+        let placed = grid.place_word("ab", 0, 0, Direction::Horizontal, false);
+        assert_eq!(placed.then(|| blocks(&grid)), expected, "{}", symmetry);
+    }
+}
+
+#[test]
+fn fills_keep_every_symmetry() {
+    let graph = graph();
+    let mut blocked = 0;
+    for (symmetry, seed) in MODES.into_iter().flat_map(|symmetry| (0..8).map(move |seed| (symmetry, seed))) {
+        let mut solver = CrosswordSolver::new(graph.clone(), Some(seed), 100_000).with_symmetry(symmetry).unwrap();
+        let SolveOutcome::Solved(grid) = solver.solve(false) else {
+            panic!("no {} fill", symmetry);
+        };
+        assert_eq!(grid.symmetry(), symmetry);
+        assert!(grid.is_symmetric(), "{} breaks {} symmetry", grid.get_grid_state_signature(), symmetry);
+        blocked += usize::from(!blocks(&grid).is_empty());
+    }
+    // Some fills need blocks, so the mirroring is exercised
+    assert!(blocked > 0);
+}
+
+#[test]
+fn blocks_mirror_through_the_centre_of_a_rectangle() {
+    let graph = common::graph_for(common::config(3, 2), &["abc", "ab", "bc", "ca", "cb"]);
+
+    // (0, 2) mirrors to (1, 0) in a 3x2 grid
+    let mut grid = CrosswordGrid::new(graph);
+    grid.set_symmetry(Symmetry::Rotational).unwrap();
+    assert!(grid.place_word("ab", 0, 0, Direction::Horizontal, false));
+    assert_eq!(blocks(&grid), [(0, 2), (1, 0)]);
+    assert!(grid.is_symmetric());
+}
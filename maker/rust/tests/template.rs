@@ -0,0 +1,54 @@
+use crossword_grapher::{CrosswordGrid, CrosswordSolver, Direction, LanguageProfile, SolveOutcome, Template};
+
+mod common;
+
+fn blocks(rows: &[Vec<char>]) -> Vec<(usize, usize)> {
+    let cells = rows.iter().enumerate().flat_map(|(row, cells)| cells.iter().enumerate().map(move |(col, &cell)| (row, col, cell)));
+    cells.filter(|&(_, _, cell)| cell == '@').map(|(row, col, _)| (row, col)).collect()
+}
+
+// Every fill of `rows` from `words`, each checked to keep its blocks
+fn fills(rows: &str, words: &[&str]) -> Vec<CrosswordGrid> {
+    let template = Template::parse(rows, &LanguageProfile::english()).unwrap();
+    let graph = common::graph_for(common::config(3, 3), words);
+    let mut solver = CrosswordSolver::new(graph, Some(7), 10_000).with_template(&template).unwrap();
+    let enumeration = solver.enumerate(100, false, false, |_| {});
+    assert!(matches!(enumeration.outcome, SolveOutcome::Exhausted));
+    for grid in &enumeration.solutions {
+        assert!(grid.is_complete());
+        assert_eq!(blocks(grid.cells()), blocks(template.rows()), "{}", grid.get_grid_state_signature());
+    }
+    enumeration.solutions
+}
+
+const WORDS: [&str; 12] = ["abc", "bca", "cab", "acb", "bac", "cba", "ab", "bc", "ca", "ba", "ac", "cb"];
+
+#[test]
+fn fills_keep_the_template_layout() {
+    // Too few three-letter words for open rows; shorter words only fit if
+    // the solver adds blocks, which it may not
+    assert!(fills("...\n...\n...", &["abc", "ab", "ba", "bc", "cb", "ca", "ac"]).is_empty());
+
+    assert_eq!(fills("...\n...\n...", &WORDS).len(), 6);
+    assert!(!fills("..#\n...\n#..", &WORDS).is_empty());
+}
+
+#[test]
+fn seed_words_placed_before_the_template_stay() {
+    let template = Template::parse("..#\n...\n#..", &LanguageProfile::english()).unwrap();
+    let graph = common::graph_for(common::config(3, 3), &WORDS);
+    let seeded = |word: &str| CrosswordSolver::new(graph.clone(), Some(7), 10_000).with_placed_word(word, 0, 0, Direction::Horizontal).unwrap();
+
+    let mut solver = seeded("ab").with_template(&template).unwrap();
+    let enumeration = solver.enumerate(100, false, false, |_| {});
+    assert!(!enumeration.solutions.is_empty());
+    for grid in &enumeration.solutions {
+        assert_eq!(grid.cells()[0][..2], ['a', 'b'], "{}", grid.get_grid_state_signature());
+    }
+
+    // "abc" runs into the template's block at (0, 2)
+    let Err(error) = seeded("abc").with_template(&template) else {
+        panic!("'abc' placed over a block");
+    };
+    assert!(error.contains("cannot place 'abc'"), "{}", error);
+}
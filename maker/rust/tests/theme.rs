@@ -0,0 +1,77 @@
+use crossword_grapher::{CrosswordGraph, CrosswordSolver, Direction, SeedWord, SolveOutcome, Theme};
+use std::collections::HashSet;
+
+mod common;
+
+fn graph() -> CrosswordGraph {
+    common::graph(&["ab", "bc", "ba", "cb", "ac", "ca"])
+}
+
+fn fills(solver: &mut CrosswordSolver) -> Vec<String> {
+    let enumeration = solver.enumerate(100, false, false, |_| {});
+    assert!(matches!(enumeration.outcome, SolveOutcome::Exhausted));
+    let mut found: Vec<String> = enumeration.solutions.iter().map(|grid| grid.get_grid_state_signature()).collect();
+    found.sort();
+    found
+}
+
+fn words(words: &[&str]) -> HashSet<String> {
+    words.iter().map(|word| word.to_string()).collect()
+}
+
+#[test]
+fn seed_words_are_placed_or_required() {
+    let theme = Theme {
+        required: words(&["ab", "ba"]),
+        ..Theme::default()
+    };
+    let mut solver = CrosswordSolver::new(graph(), Some(7), 10_000)
+        .with_placed_word("ab", 0, 0, Direction::Horizontal)
+        .unwrap()
+        .with_theme(theme);
+    assert_eq!(fills(&mut solver), ["abca"]);
+
+    let solver = CrosswordSolver::new(graph(), Some(7), 10_000).with_placed_word("ab", 1, 1, Direction::Vertical);
+    assert!(solver.is_err());
+
+    let seed: SeedWord = "Kara@0,2,v".parse().unwrap();
+    assert_eq!((seed.word.as_str(), seed.position), ("Kara", Some((0, 2, Direction::Vertical))));
+    assert_eq!("kara".parse::<SeedWord>().unwrap().position, None);
+    assert!("kara@0,2".parse::<SeedWord>().is_err());
+    assert!("kara@0,2,x".parse::<SeedWord>().is_err());
+}
+
+#[test]
+fn seed_words_off_the_grid_are_refused() {
+    let off_grid = [
+        (9, 0, Direction::Horizontal),
+        (0, 9, Direction::Vertical),
+        (0, 1, Direction::Horizontal),
+        (1, 0, Direction::Vertical),
+    ];
+    for (row, col, direction) in off_grid {
+        let solver = CrosswordSolver::new(graph(), Some(7), 10_000).with_placed_word("ab", row, col, direction);
+        let Err(error) = solver else {
+            panic!("'ab' placed at ({}, {}) {:?}", row, col, direction);
+        };
+        assert!(error.contains("runs off the 2x2 grid"), "{}", error);
+    }
+}
+
+#[test]
+fn enough_theme_words_have_to_land() {
+    let theme = Theme {
+        words: words(&["bc", "cb"]),
+        min: 2,
+        ..Theme::default()
+    };
+    let mut solver = CrosswordSolver::new(graph(), Some(7), 10_000).with_theme(theme.clone());
+    let found = fills(&mut solver);
+    assert_eq!(found, ["bacb", "bcab", "cabc", "cbac"]);
+
+    let mut solver = CrosswordSolver::new(graph(), Some(7), 10_000).with_theme(theme.clone()).with_heuristic(true);
+    let SolveOutcome::Solved(grid) = solver.solve(false) else {
+        panic!("no themed fill found");
+    };
+    assert_eq!(theme.landed(&grid), ["bc", "cb"]);
+}
@@ -0,0 +1,56 @@
+use crossword_grapher::{CrosswordGraph, GraphConfig, Word};
+
+const DICTIONARY: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../turkish_words.txt");
+
+fn load_graph(size: usize, min_len: usize) -> CrosswordGraph {
+    let mut graph = CrosswordGraph::new(GraphConfig::square(size, min_len));
+    graph.load_words(DICTIONARY, false).unwrap();
+    graph
+}
+
+#[test]
+fn five_letter_words_with_turkish_letters_are_kept() {
+    let graph = load_graph(5, 5);
+    for word in ["şerit", "kanıt", "çiçek", "güneş", "ödeme"] {
+        assert!(graph.words().contains(word), "'{}' missing from 5x5 words", word);
+    }
+}
+
+#[test]
+fn four_letter_words_with_turkish_letters_fit_a_four_by_four_grid() {
+    let graph = load_graph(4, 4);
+    for word in ["ağaç", "ağır", "çalı", "ışık", "kapı", "örgü", "üzüm"] {
+        assert!(graph.words().contains(word), "'{}' missing from 4x4 words", word);
+    }
+    assert!(!graph.words().contains("şerit"));
+}
+
+#[test]
+fn intermediaries_mask_letters_not_bytes() {
+    let graph = load_graph(5, 5);
+    let intermediaries = graph.generate_intermediaries_for_word("şerit");
+
+    assert_eq!(intermediaries.len(), (1 << 5) - 2);
+    assert!(intermediaries.iter().all(|i| i.chars().count() == 5));
+    assert!(intermediaries.contains("ş____"));
+    assert!(intermediaries.contains("_erit"));
+}
+
+#[test]
+fn pattern_lookup_finds_words_with_turkish_letters() {
+    let mut graph = load_graph(5, 5);
+    graph.build_graph(false);
+
+    assert!(graph.get_words_for_pattern("g___ş").contains("güneş"));
+    assert!(graph.get_words_for_pattern("_____").contains("şerit"));
+    assert!(graph.words_match_pattern("güneş", "g___ş"));
+    assert!(!graph.words_match_pattern("güneş", "g___"));
+}
+
+#[test]
+fn word_length_counts_characters() {
+    let word = Word::new("ışık");
+    assert_eq!(word.len(), 4);
+    assert_eq!(word.char_at(0), Some('ı'));
+    assert_eq!(word.to_string(), "ışık");
+}
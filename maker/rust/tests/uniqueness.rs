@@ -0,0 +1,35 @@
+use crossword_grapher::{CrosswordGraph, CrosswordGrid, CrosswordSolver, LanguageProfile, Template, Uniqueness};
+
+mod common;
+
+fn solved(words: &[&str], rows: &str) -> (CrosswordGraph, CrosswordGrid) {
+    let template = Template::parse(rows, &LanguageProfile::english()).unwrap();
+    let graph = common::graph_for(common::config(template.width(), template.height()), words);
+    let grid = CrosswordGrid::from_template(graph.clone(), &template).unwrap();
+    (graph, grid)
+}
+
+#[test]
+fn a_forced_fill_is_unique() {
+    // Rows and columns must be "ab" then "bc"; swapping the rows breaks the columns
+    let (graph, grid) = solved(&["ab", "bc"], "ab\nbc");
+    let uniqueness = CrosswordSolver::new(graph, Some(1), 1000).find_alternatives(&grid, 3, false);
+    assert!(matches!(uniqueness, Uniqueness::Unique), "{:?}", uniqueness);
+}
+
+#[test]
+fn other_fills_are_reported() {
+    let (graph, grid) = solved(&["ab", "bc", "ba", "cb"], "ab\nbc");
+    let uniqueness = CrosswordSolver::new(graph, Some(1), 1000).find_alternatives(&grid, 10, false);
+    let Uniqueness::Alternatives(grids) = uniqueness else {
+        panic!("expected alternatives, got {:?}", uniqueness);
+    };
+    let fills: Vec<String> = grids.iter().map(|grid| grid.get_grid_state_signature()).collect();
+    // Every row and column of the layout is an entry, so fills such as abba
+    // that use a word twice do not count
+    let mut expected = vec!["bacb", "bcab"];
+    let mut fills: Vec<&str> = fills.iter().map(String::as_str).collect();
+    fills.sort();
+    expected.sort();
+    assert_eq!(fills, expected);
+}
@@ -67,7 +67,8 @@ Promise.all([
   return puzzleTextPromise;
 }).then(text => {
   console.log("Fetched puzzle data:", text);
-  const processedText = processPuzzleText(text);
+  return processPuzzleText(text, puzzleDate);
+}).then(processedText => {
   console.log("Processed puzzle data:", processedText);
   const lines = processedText.trim().split("\n");
   const author = lines[0].split(": ")[1];
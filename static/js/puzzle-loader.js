@@ -8,12 +8,37 @@ function simpleDecrypt(encryptedText) {
   ).join('');
 }
 
-// Function to process puzzle text (handle both plain and encrypted content)
-function processPuzzleText(rawText) {
+// Decrypts an [ENCRYPTED:v2] puzzle (matches encryption.rs in maker/rust):
+// AES-256-GCM keyed with HKDF-SHA256 of the publish date (YYYY-MM-DD), body is
+// base64 of the 12-byte nonce followed by the ciphertext and tag.
+async function decryptPuzzleV2(text, date) {
+  const body = text.trim().substring('[ENCRYPTED:v2]\n'.length);
+  const bytes = Uint8Array.from(atob(body), c => c.charCodeAt(0));
+  const encoder = new TextEncoder();
+  const baseKey = await crypto.subtle.importKey('raw', encoder.encode(date), 'HKDF', false, ['deriveKey']);
+  const key = await crypto.subtle.deriveKey(
+    { name: 'HKDF', hash: 'SHA-256', salt: encoder.encode('crossword-puzzle'), info: encoder.encode('[ENCRYPTED:v2] aes-256-gcm') },
+    baseKey,
+    { name: 'AES-GCM', length: 256 },
+    false,
+    ['decrypt']
+  );
+  const plain = await crypto.subtle.decrypt({ name: 'AES-GCM', iv: bytes.slice(0, 12) }, key, bytes.slice(12));
+  return new TextDecoder().decode(plain);
+}
+
+// Function to process puzzle text (handle both plain and encrypted content).
+// `date` is the puzzle's YYYY-MM-DD file name, which keys [ENCRYPTED:v2].
+async function processPuzzleText(rawText, date) {
   const text = rawText.trim();
   
   // Check if content is encrypted
-  if (text.startsWith('[ENCRYPTED]')) {
+  if (text.startsWith('[ENCRYPTED:v2]')) {
+    console.log("Detected v2 encrypted content, decrypting...");
+    const decryptedContent = await decryptPuzzleV2(text, date);
+    console.log("Content decrypted successfully");
+    return decryptedContent;
+  } else if (text.startsWith('[ENCRYPTED]')) {
     console.log("Detected encrypted content, decrypting...");
     const encryptedContent = text.substring('[ENCRYPTED]\n'.length);
     const decryptedContent = simpleDecrypt(encryptedContent);
@@ -78,3 +103,7 @@ async function findLatestPuzzle() {
   // Fallback to today's date if nothing found
   return currentDate.toISOString().slice(0, 10);
 }
+
+if (typeof module !== 'undefined') {
+  module.exports = { simpleDecrypt, decryptPuzzleV2, processPuzzleText };
+}